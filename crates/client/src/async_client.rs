@@ -0,0 +1,327 @@
+//! The asynchronous Zookeeper "Four Letter Words" commands client, based on Tokio.
+//!
+//! It shares configuration (see `ZK4LWClientBuilder::build_async`) and the parsing of responses
+//! with the blocking `ZK4LWClient`: only the I/O is asynchronous.
+//!
+//! NOTE: only available with the `async` feature, and it only supports the default TCP transport
+//! (i.e. neither proxies, TLS nor custom `ZK4LWTransport`s): building it from a configuration
+//! that sets any of them fails with `ZK4LWError::UnsupportedTransportError`.
+
+use std::{
+    convert::TryFrom,
+    future::Future,
+    io,
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    time,
+};
+
+use crate::{
+    capabilities::*, client::*, commands::srvr::ZK4LWServer, errors::*, policy::*, result::*,
+};
+
+/// The asynchronous Zookeeper "Four Letter Words" client
+#[derive(Debug, Clone)]
+pub struct AsyncZK4LWClient {
+    inner: ZK4LWClient,
+}
+
+impl TryFrom<ZK4LWClient> for AsyncZK4LWClient {
+    type Error = ZK4LWError;
+
+    /// Create an asynchronous client, with the same configuration of the given (blocking) one
+    ///
+    /// Fails with `ZK4LWError::UnsupportedTransportError` if it uses any transport other than plain TCP.
+    fn try_from(client: ZK4LWClient) -> Result<Self, Self::Error> {
+        if client.transport.is_some() {
+            return Err(ZK4LWError::UnsupportedTransportError(
+                "only the default TCP transport is supported by the asynchronous client",
+            ));
+        }
+
+        Ok(AsyncZK4LWClient { inner: client })
+    }
+}
+
+impl AsyncZK4LWClient {
+    /// Create a new asynchronous ZK 4LW client, without timeouts
+    ///
+    /// # Arguments
+    /// * `host` - host in the ZK Ensemble to send commands to; can be both an IP or a Hostname
+    /// * `port` - port to send commands on
+    pub fn new<S: Into<String>>(host: S, port: u16) -> Self {
+        AsyncZK4LWClient {
+            inner: ZK4LWClient::new(host, port),
+        }
+    }
+
+    pub fn host(&self) -> &str {
+        self.inner.host()
+    }
+
+    pub fn port(&self) -> u16 {
+        self.inner.port()
+    }
+
+    pub fn parse_policy(&self) -> ZK4LWParsePolicy {
+        self.inner.parse_policy()
+    }
+
+    /// Execute the given command type and return a result containing the response
+    ///
+    /// This is a shortcut for commands that don't carry any argument:
+    /// see `execute_command` for the others.
+    pub async fn execute<C: ZK4LWCommand + Default>(&self) -> ZK4LWResult<C::Response> {
+        self.execute_command(&C::default()).await
+    }
+
+    /// Execute the given command and return a result containing the response
+    ///
+    /// # Arguments
+    /// * `command` - the command to execute
+    pub async fn execute_command<C: ZK4LWCommand>(&self, command: &C) -> ZK4LWResult<C::Response> {
+        self.execute_command_with_warnings(command)
+            .await
+            .map(|(response, _)| response)
+    }
+
+    /// Execute the given command type and return a result containing the response,
+    /// together with the warnings collected parsing it (only with `ZK4LWParsePolicy::LENIENT`)
+    pub async fn execute_with_warnings<C: ZK4LWCommand + Default>(
+        &self,
+    ) -> ZK4LWResult<(C::Response, Vec<ZK4LWParseWarning>)> {
+        self.execute_command_with_warnings(&C::default()).await
+    }
+
+    /// Execute the given command and return a result containing the response,
+    /// together with the warnings collected parsing it (only with `ZK4LWParsePolicy::LENIENT`)
+    ///
+    /// # Arguments
+    /// * `command` - the command to execute
+    pub async fn execute_command_with_warnings<C: ZK4LWCommand>(
+        &self,
+        command: &C,
+    ) -> ZK4LWResult<(C::Response, Vec<ZK4LWParseWarning>)> {
+        let request_body = command.request_body();
+
+        let mut attempt = 1;
+        loop {
+            let mut request_sent = false;
+            let error = match self
+                .execute_request_once::<C>(&request_body, &mut request_sent)
+                .await
+            {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            // Retry after a delay, if the policy allows it
+            if C::MUTATING && request_sent {
+                return Err(error);
+            }
+            match self.inner.retry_delay(attempt, &error) {
+                Some(delay) => time::sleep(delay).await,
+                None => return Err(error),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Send the given request once, without retrying, and build the response of the command
+    ///
+    /// `request_sent` is set once the client starts sending the request to the server.
+    async fn execute_request_once<C: ZK4LWCommand>(
+        &self,
+        request_body: &[u8],
+        request_sent: &mut bool,
+    ) -> ZK4LWResult<(C::Response, Vec<ZK4LWParseWarning>)> {
+        let deadline = self.inner.deadline.map(|d| Instant::now() + d);
+
+        // Connect TCP socket to ZooKeeper server
+        let timeout = phase_timeout(self.inner.connect_timeout, deadline, ZK4LWPhase::CONNECT)?;
+        let mut stream = with_timeout(
+            timeout,
+            ZK4LWPhase::CONNECT,
+            TcpStream::connect((self.inner.host.as_str(), self.inner.port)),
+        )
+        .await?;
+        stream.set_nodelay(true)?;
+
+        // Send 4LW command
+        let timeout = phase_timeout(self.inner.write_timeout, deadline, ZK4LWPhase::WRITE)?;
+        *request_sent = true;
+        with_timeout(timeout, ZK4LWPhase::WRITE, stream.write_all(request_body)).await?;
+
+        // Read the whole response to buffer (or until the command deems it complete)
+        let mut response_buffer = Vec::new();
+        let mut chunk = [0u8; READ_CHUNK_SIZE];
+        while !C::is_response_complete(&response_buffer) {
+            let timeout = phase_timeout(self.inner.read_timeout, deadline, ZK4LWPhase::READ)?;
+            match with_timeout(timeout, ZK4LWPhase::READ, stream.read(&mut chunk)).await? {
+                0 => break,
+                n => response_buffer.extend_from_slice(&chunk[..n]),
+            }
+        }
+
+        build_command_response::<C>(&response_buffer, self.inner.parse_policy)
+    }
+
+    /// Probe the server (via the Server command) and return its capabilities
+    ///
+    /// See `ZK4LWClient::capabilities`.
+    pub async fn capabilities(&self) -> ZK4LWResult<ZK4LWCapabilities> {
+        let srvr = self.execute::<ZK4LWServer>().await?;
+        Ok(ZK4LWCapabilities::for_version(srvr.version))
+    }
+
+    /// Execute the given command type, that only the leader can answer (e.g. `ZK4LWDump`),
+    /// after checking the state of the server (via the Server command)
+    ///
+    /// See `ZK4LWClient::execute_leader_only`.
+    pub async fn execute_leader_only<C: ZK4LWCommand + Default>(&self) -> ZK4LWResult<C::Response> {
+        let srvr = self.execute::<ZK4LWServer>().await?;
+        if !srvr.server_state.tracks_sessions() {
+            return Err(ZK4LWError::NotLeaderError(C::NAME, Some(srvr.server_state)));
+        }
+
+        self.execute::<C>().await
+    }
+}
+
+/// Awaits the I/O operation of the given phase, failing with `ZK4LWError::Timeout` if it takes too long
+async fn with_timeout<T, F>(timeout: Option<Duration>, phase: ZK4LWPhase, io: F) -> ZK4LWResult<T>
+where
+    F: Future<Output = io::Result<T>>,
+{
+    let result = match timeout {
+        Some(timeout) => time::timeout(timeout, io)
+            .await
+            .map_err(|_| ZK4LWError::Timeout(phase))?,
+        None => io.await,
+    };
+
+    result.map_err(|e| phase_error(e, phase))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{Read, Write},
+        net, thread,
+        time::Duration,
+    };
+
+    use crate::async_client::AsyncZK4LWClient;
+    use crate::client::ZK4LWClient;
+    use crate::commands::mntr::ZK4LWMonitor;
+    use crate::commands::reset::ZK4LWResetStats;
+    use crate::commands::ruok::{ZK4LWRuok, ZK4LWRuokResponse};
+    use crate::errors::{ZK4LWError, ZK4LWPhase};
+    use crate::retry::ZK4LWBackoffRetryPolicy;
+    use crate::state::ZK4LWServerState::LEADER;
+    use crate::transport::memory::ZK4LWMemoryTransport;
+    use crate::transport::proxy::ZK4LWProxy;
+
+    #[tokio::test]
+    async fn should_execute_commands_asynchronously() {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let mntr_resp_body = fs::read("../../fixtures/3.6/mntr.response").unwrap();
+        let server = thread::spawn(move || {
+            for response in [b"imok".to_vec(), mntr_resp_body].iter() {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0u8; 4];
+                stream.read_exact(&mut request).unwrap();
+                stream.write_all(response).unwrap();
+            }
+        });
+
+        let client = AsyncZK4LWClient::new("127.0.0.1", port);
+        assert_eq!(
+            client.execute::<ZK4LWRuok>().await.unwrap(),
+            ZK4LWRuokResponse::IMOK
+        );
+        let mntr_resp = client.execute::<ZK4LWMonitor>().await.unwrap();
+        assert!(mntr_resp.server_state == LEADER);
+        assert_eq!(mntr_resp.znode_count, 5);
+
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn should_time_out_if_server_does_not_respond() {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // Server that accepts the connection, but never responds
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut rest = Vec::new();
+            let _ = stream.read_to_end(&mut rest);
+        });
+
+        let client = ZK4LWClient::builder()
+            .host("127.0.0.1")
+            .port(port)
+            .deadline(Duration::from_millis(100))
+            .build_async()
+            .unwrap();
+        match client.execute::<ZK4LWMonitor>().await {
+            Err(ZK4LWError::Timeout(phase)) => assert_eq!(phase, ZK4LWPhase::READ),
+            _ => panic!("Expected Timeout"),
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn should_refuse_to_build_with_unsupported_transports() {
+        let builders = vec![
+            ZK4LWClient::builder()
+                .transport(ZK4LWMemoryTransport::new().with_response("ruok", "imok")),
+            ZK4LWClient::builder().proxy(ZK4LWProxy::socks5("bastion", 1080)),
+        ];
+        for builder in builders {
+            match builder.build_async() {
+                Err(ZK4LWError::UnsupportedTransportError(_)) => {}
+                _ => panic!("Expected UnsupportedTransportError"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn should_not_retry_mutating_commands_once_sent() {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // Server that is not serving requests, and closes the listener after the first one
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 4];
+            stream.read_exact(&mut request).unwrap();
+            stream
+                .write_all(b"This ZooKeeper instance is not currently serving requests\n")
+                .unwrap();
+        });
+
+        let client = ZK4LWClient::builder()
+            .host("127.0.0.1")
+            .port(port)
+            .retry_policy(ZK4LWBackoffRetryPolicy {
+                initial_delay: Duration::from_millis(1),
+                ..ZK4LWBackoffRetryPolicy::new(3)
+            })
+            .build_async()
+            .unwrap();
+        match client.execute::<ZK4LWResetStats>().await {
+            Err(ZK4LWError::NotServingError) => {}
+            _ => panic!("Expected NotServingError"),
+        }
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,236 @@
+//! Capabilities of a Zookeeper Server, that depend on its version.
+//!
+//! Different versions of Zookeeper support different "Four Letter Words" commands,
+//! and report different fields via the Monitor (i.e. "mntr") command.
+//! Tooling that targets servers of mixed versions can use `ZK4LWCapabilities`
+//! instead of hard-coding this matrix.
+
+use crate::{
+    client::*,
+    commands::{
+        conf::*, cons::*, dirs::*, dump::*, envi::*, hash::*, isro::*, mntr::*, reset::*, ruok::*,
+        srvr::*, stat::*, tracemask::*, watches::*,
+    },
+    version::*,
+};
+
+/// Commands supported by the client, each with the version of Zookeeper that introduced it
+const COMMANDS_SINCE: &[(&str, (u32, u32, u32))] = &[
+    (ZK4LWConf::NAME, (3, 3, 0)),
+    (ZK4LWConnections::NAME, (3, 3, 0)),
+    (ZK4LWResetConnectionStats::NAME, (3, 3, 0)),
+    (ZK4LWDump::NAME, (3, 3, 0)),
+    (ZK4LWEnvironment::NAME, (3, 3, 0)),
+    (ZK4LWRuok::NAME, (3, 3, 0)),
+    (ZK4LWResetStats::NAME, (3, 3, 0)),
+    (ZK4LWServer::NAME, (3, 3, 0)),
+    (ZK4LWStat::NAME, (3, 3, 0)),
+    (ZK4LWWatchesSummary::NAME, (3, 3, 0)),
+    (ZK4LWWatchesBySession::NAME, (3, 3, 0)),
+    (ZK4LWWatchesByPath::NAME, (3, 3, 0)),
+    (ZK4LWGetTraceMask::NAME, (3, 3, 0)),
+    (ZK4LWSetTraceMask::NAME, (3, 3, 0)),
+    (ZK4LWMonitor::NAME, (3, 4, 0)),
+    (ZK4LWIsReadOnly::NAME, (3, 4, 0)),
+    (ZK4LWDirs::NAME, (3, 5, 1)),
+    (ZK4LWHash::NAME, (3, 6, 0)),
+];
+
+/// Fields reported by the Monitor command by every version of Zookeeper
+const MNTR_FIELDS: &[&str] = &[
+    "zk_version",
+    "zk_avg_latency",
+    "zk_max_latency",
+    "zk_min_latency",
+    "zk_packets_received",
+    "zk_packets_sent",
+    "zk_num_alive_connections",
+    "zk_outstanding_requests",
+    "zk_server_state",
+    "zk_znode_count",
+    "zk_watch_count",
+    "zk_ephemerals_count",
+    "zk_approximate_data_size",
+    "zk_last_proposal_size",
+    "zk_max_proposal_size",
+    "zk_min_proposal_size",
+];
+
+/// Fields reported by the Monitor command by every version of Zookeeper, only on Unix platforms
+const MNTR_UNIX_FIELDS: &[&str] = &[
+    "zk_open_file_descriptor_count",
+    "zk_max_file_descriptor_count",
+];
+
+/// Fields reported by the Monitor command, since ZK 3.6.x
+const MNTR_FIELDS_SINCE_36: &[&str] = &[
+    "zk_peer_state",
+    "zk_uptime",
+    "zk_global_sessions",
+    "zk_local_sessions",
+    "zk_bytes_received_count",
+    "zk_digest_mismatches_count",
+    "zk_unrecoverable_error_count",
+];
+
+/// Fields reported by the Monitor command only by the "Leader"
+const MNTR_LEADER_FIELDS: &[&str] = &["zk_synced_followers", "zk_pending_syncs"];
+
+/// Fields reported by the Monitor command only by the "Leader", since ZK 3.6.x
+const MNTR_LEADER_FIELDS_SINCE_36: &[&str] = &[
+    "zk_synced_non_voting_followers",
+    "zk_synced_observers",
+    "zk_leader_uptime",
+];
+
+const MNTR_FOLLOWERS_FIELD: &str = "zk_followers";
+const MNTR_LEARNERS_FIELD: &str = "zk_learners";
+
+/// Capabilities of a Zookeeper server, derived from its version
+///
+/// Use `ZK4LWClient::capabilities` to probe a server, or `ZK4LWCapabilities::for_version`
+/// if the version is already known.
+///
+/// NOTE: Commands can still be refused by the server if they are not in its whitelist
+/// (i.e. `4lw.commands.whitelist`, since ZK 3.5.3): this only reports what the version supports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZK4LWCapabilities {
+    pub version: ZK4LWVersion,
+    /// Names of the "Four Letter Words" commands supported (e.g. `mntr`)
+    pub commands: Vec<&'static str>,
+    /// Fields to expect in the response of the Monitor command, from every server
+    pub mntr_fields: Vec<&'static str>,
+    /// Fields to expect in the response of the Monitor command, only from servers running on Unix
+    ///
+    /// NOTE: the version doesn't tell the platform: these are missing from other servers.
+    pub mntr_unix_fields: Vec<&'static str>,
+    /// Fields to expect in the response of the Monitor command, only from the "Leader"
+    pub mntr_leader_fields: Vec<&'static str>,
+    /// Field that reports the number of learners in the response of the Monitor command
+    ///
+    /// NOTE: it was `zk_followers` in ZK < 3.6.x, and `zk_learners` since.
+    pub mntr_learners_field: &'static str,
+    /// Whether the Monitor command reports the (many) metrics of the Metrics System (since ZK 3.6.x)
+    pub mntr_metrics_system: bool,
+    /// Whether the AdminServer (i.e. HTTP API) is likely available (since ZK 3.5.x)
+    ///
+    /// NOTE: it's enabled by default, but can be disabled via `admin.enableServer`.
+    pub admin_server: bool,
+}
+
+impl ZK4LWCapabilities {
+    /// Returns the capabilities of a Zookeeper server of the given version
+    pub fn for_version(version: ZK4LWVersion) -> Self {
+        let since = |(major, minor, patch): (u32, u32, u32)| {
+            (version.major, version.minor, version.patch) >= (major, minor, patch)
+        };
+        let since_36 = since((3, 6, 0));
+
+        let commands = COMMANDS_SINCE
+            .iter()
+            .filter(|(_, since_version)| since(*since_version))
+            .map(|(name, _)| *name)
+            .collect();
+
+        let mut mntr_fields = MNTR_FIELDS.to_vec();
+        let mut mntr_leader_fields = MNTR_LEADER_FIELDS.to_vec();
+        let mntr_learners_field = if since_36 {
+            mntr_fields.extend_from_slice(MNTR_FIELDS_SINCE_36);
+            mntr_leader_fields.extend_from_slice(MNTR_LEADER_FIELDS_SINCE_36);
+            MNTR_LEARNERS_FIELD
+        } else {
+            MNTR_FOLLOWERS_FIELD
+        };
+        mntr_leader_fields.push(mntr_learners_field);
+
+        ZK4LWCapabilities {
+            commands,
+            mntr_fields,
+            mntr_unix_fields: MNTR_UNIX_FIELDS.to_vec(),
+            mntr_leader_fields,
+            mntr_learners_field,
+            mntr_metrics_system: since_36,
+            admin_server: since((3, 5, 0)),
+            version,
+        }
+    }
+
+    /// Returns `true` if the "Four Letter Words" command with the given name is supported
+    pub fn supports(&self, command_name: &str) -> bool {
+        self.commands.contains(&command_name)
+    }
+
+    /// Returns `true` if the given command type is supported
+    pub fn supports_command<C: ZK4LWCommand>(&self) -> bool {
+        self.supports(C::NAME)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::capabilities::ZK4LWCapabilities;
+    use crate::client::ZK4LWCommand;
+    use crate::commands::dirs::ZK4LWDirs;
+    use crate::commands::hash::ZK4LWHash;
+    use crate::commands::mntr::ZK4LWMonitor;
+    use crate::commands::srvr::ZK4LWServer;
+    use crate::parsing::tab_separated_bytes_to_key_value;
+
+    #[test]
+    fn should_derive_capabilities_from_version() {
+        for version in ["3.4", "3.5", "3.6"].iter() {
+            let srvr_resp_body =
+                fs::read_to_string(format!("../../fixtures/{}/srvr.response", version)).unwrap();
+            let srvr_resp = ZK4LWServer::build_response(srvr_resp_body.as_str()).unwrap();
+            let caps = ZK4LWCapabilities::for_version(srvr_resp.version);
+
+            // Every expected field is reported by the (leader) fixture
+            let mntr_resp_body =
+                fs::read_to_string(format!("../../fixtures/{}/mntr.response", version)).unwrap();
+            let mntr_fields = tab_separated_bytes_to_key_value(mntr_resp_body.as_str()).unwrap();
+            for field in caps
+                .mntr_fields
+                .iter()
+                .chain(&caps.mntr_unix_fields)
+                .chain(&caps.mntr_leader_fields)
+            {
+                assert!(mntr_fields.contains_key(field), "{} in {}", field, version);
+            }
+
+            assert!(caps.supports_command::<ZK4LWMonitor>());
+            assert!(caps.supports("ruok"));
+            assert!(!caps.supports("nope"));
+        }
+    }
+
+    #[test]
+    fn should_tell_apart_capabilities_of_versions() {
+        let caps_34 = ZK4LWCapabilities::for_version("3.4.14".parse().unwrap());
+        assert!(!caps_34.supports_command::<ZK4LWDirs>());
+        assert!(!caps_34.supports_command::<ZK4LWHash>());
+        assert_eq!(caps_34.mntr_learners_field, "zk_followers");
+        assert!(!caps_34.mntr_metrics_system);
+        assert!(!caps_34.admin_server);
+        assert!(!caps_34
+            .mntr_fields
+            .contains(&"zk_open_file_descriptor_count"));
+        assert!(caps_34
+            .mntr_unix_fields
+            .contains(&"zk_open_file_descriptor_count"));
+
+        let caps_35 = ZK4LWCapabilities::for_version("3.5.8".parse().unwrap());
+        assert!(caps_35.supports_command::<ZK4LWDirs>());
+        assert!(!caps_35.supports_command::<ZK4LWHash>());
+        assert_eq!(caps_35.mntr_learners_field, "zk_followers");
+        assert!(caps_35.admin_server);
+
+        let caps_36 = ZK4LWCapabilities::for_version("3.6.1".parse().unwrap());
+        assert!(caps_36.supports_command::<ZK4LWHash>());
+        assert_eq!(caps_36.mntr_learners_field, "zk_learners");
+        assert!(caps_36.mntr_metrics_system);
+        assert!(caps_36.admin_server);
+        assert_eq!(caps_36.commands.len(), 18);
+    }
+}
@@ -3,66 +3,744 @@
 //! This module includes both the Trait implemented by commands
 //! and the client to execute those commands.
 
+#[cfg(feature = "async")]
+use std::convert::TryFrom;
 use std::{
-    io::{Read, Write},
-    net, str,
+    borrow::Cow,
+    io, str,
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
 };
 
-use crate::result::*;
+#[cfg(feature = "async")]
+use crate::async_client::*;
+use crate::ensemble::*;
+#[cfg(feature = "tls")]
+use crate::transport::tls::*;
+use crate::{
+    capabilities::*, commands::srvr::ZK4LWServer, errors::*, policy::*, result::*, retry::*,
+    transport::proxy::*, transport::tcp::*, transport::*,
+};
+
+pub(crate) const READ_CHUNK_SIZE: usize = 4096;
+const DEFAULT_HOST: &str = "localhost";
+const DEFAULT_PORT: u16 = 2181;
+const RESPONSE_NOT_WHITELISTED_SUFFIX: &str = "is not executed because it is not in the whitelist.";
+static DEFAULT_TRANSPORT: ZK4LWTcpTransport = ZK4LWTcpTransport::new();
+const RESPONSE_NOT_SERVING: &str = "This ZooKeeper instance is not currently serving requests";
 
 /// Trait that defines how a Zookeeper "Four Letter Words" command looks like
 pub trait ZK4LWCommand {
     /// Response produced by a successful execution of the command
     type Response;
 
-    /// Returns an `str` needed to send the request
-    fn request_body() -> &'static str;
+    /// The "Four Letter Word" that identifies the command (e.g. `mntr`)
+    const NAME: &'static str;
+
+    /// Whether the command changes the state of the server (e.g. resets statistics)
+    ///
+    /// Read-only tooling can check this value to refuse to send commands that set it to `true`.
+    const MUTATING: bool = false;
+
+    /// Returns the bytes needed to send the request
+    ///
+    /// By default this is just the command `NAME`:
+    /// commands that carry an argument (e.g. `stmk`) append it as payload.
+    fn request_body(&self) -> Cow<'static, [u8]> {
+        Cow::Borrowed(Self::NAME.as_bytes())
+    }
+
+    /// Returns `true` if the response received so far is already complete
+    ///
+    /// By default the client reads until the server closes the connection:
+    /// commands that can recognise their complete response can stop reading sooner.
+    ///
+    /// # Arguments
+    /// * `partial_response_body` - A slice containing the raw response body received so far
+    fn is_response_complete(_partial_response_body: &[u8]) -> bool {
+        false
+    }
 
     /// Builds and returns a `ZK4LWResult` of `Response` type
     ///
     /// # Arguments
     /// * `response_body` - A `str` slice containing the raw response body from a given request
     fn build_response(response_body: &str) -> ZK4LWResult<Self::Response>;
+
+    /// Builds and returns a `ZK4LWResult` of `Response` type, applying the policy of the given parser
+    ///
+    /// By default the policy is ignored, and this is the same as `build_response`:
+    /// commands that map fields by key (e.g. `mntr`) enforce required fields, or collect warnings.
+    ///
+    /// # Arguments
+    /// * `response_body` - A `str` slice containing the raw response body from a given request
+    /// * `parser` - The parser that applies the `ZK4LWParsePolicy`, and collects warnings
+    fn build_response_with_parser(
+        response_body: &str,
+        _parser: &mut ZK4LWParser,
+    ) -> ZK4LWResult<Self::Response> {
+        Self::build_response(response_body)
+    }
 }
 
-/// The Zookeeper "Four Letter Words" client
-pub struct ZK4LWClient {
+/// Builder of `ZK4LWClient`, to configure timeouts and other options
+///
+/// By default the client connects to `localhost:2181`, and no timeout is set.
+#[derive(Debug, Clone)]
+pub struct ZK4LWClientBuilder {
     host: String,
     port: u16,
+    parse_policy: ZK4LWParsePolicy,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+    deadline: Option<Duration>,
+    retry_policy: Option<Arc<dyn ZK4LWRetryPolicy>>,
+    transport: Option<Arc<dyn ZK4LWTransport>>,
+    proxy: Option<ZK4LWProxy>,
+    #[cfg(feature = "tls")]
+    tls: Option<ZK4LWTlsConfig>,
+}
+
+impl Default for ZK4LWClientBuilder {
+    fn default() -> Self {
+        Self {
+            host: DEFAULT_HOST.into(),
+            port: DEFAULT_PORT,
+            parse_policy: ZK4LWParsePolicy::default(),
+            connect_timeout: None,
+            read_timeout: None,
+            write_timeout: None,
+            deadline: None,
+            retry_policy: None,
+            transport: None,
+            proxy: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+}
+
+impl ZK4LWClientBuilder {
+    /// Host in the ZK Ensemble to send commands to; can be both an IP or a Hostname
+    pub fn host<S: Into<String>>(mut self, host: S) -> Self {
+        self.host = host.into();
+        self
+    }
+
+    /// Port to send commands on
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Policy applied when parsing responses (default: `ZK4LWParsePolicy::STRICT`)
+    pub fn parse_policy(mut self, parse_policy: ZK4LWParsePolicy) -> Self {
+        self.parse_policy = parse_policy;
+        self
+    }
+
+    /// Maximum time to wait for the connection to the server to be established
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Maximum time to wait for each read from the server
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Maximum time to wait for each write to the server
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    /// Maximum time for the whole execution of a command, from connection to the end of the response
+    ///
+    /// This bounds commands that keep receiving data slowly, that a read timeout alone wouldn't.
+    /// NOTE: if a retry policy is set, the deadline applies to each attempt.
+    pub fn deadline(mut self, deadline: Duration) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Policy to retry failed executions of commands (default: no retries)
+    ///
+    /// See `ZK4LWBackoffRetryPolicy` for exponential backoff with jitter.
+    /// NOTE: commands that are `ZK4LWCommand::MUTATING` are only retried if they failed
+    /// before the request was sent, as the server might have already executed them.
+    pub fn retry_policy<P: ZK4LWRetryPolicy + 'static>(mut self, retry_policy: P) -> Self {
+        self.retry_policy = Some(Arc::new(retry_policy));
+        self
+    }
+
+    /// Transport to send commands over (default: `ZK4LWTcpTransport`)
+    ///
+    /// NOTE: this takes precedence over `proxy` and `tls`, that configure the default transports.
+    pub fn transport<T: ZK4LWTransport + 'static>(mut self, transport: T) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Tunnel the connections to the server through the given proxy (default: direct connection)
+    ///
+    /// This is a shortcut for setting a `ZK4LWTcpTransport` (or `ZK4LWTlsTransport`) with proxy.
+    /// NOTE: not supported by the asynchronous client (see `build_async`).
+    pub fn proxy(mut self, proxy: ZK4LWProxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Connect to the server over TLS, with the given configuration (default: plaintext)
+    ///
+    /// This is a shortcut for setting a `ZK4LWTlsTransport` as transport.
+    /// NOTE: the port must be the TLS client port of the server (i.e. `secureClientPort`).
+    /// Not supported by the asynchronous client (see `build_async`).
+    #[cfg(feature = "tls")]
+    pub fn tls(mut self, tls: ZK4LWTlsConfig) -> Self {
+        self.tls = Some(tls);
+        self
+    }
+
+    /// Build a `ZK4LWEnsembleClient` for the servers in the given connect string,
+    /// each with the same configuration (host and port excluded)
+    ///
+    /// # Arguments
+    /// * `connect_string` - the connect string of the Ensemble (e.g. `zk1:2181,zk2:2181,zk3:2181/chroot`)
+    pub fn build_ensemble(self, connect_string: &str) -> ZK4LWResult<ZK4LWEnsembleClient> {
+        ZK4LWEnsembleClient::with_builder(connect_string, self)
+    }
+
+    /// Build an `AsyncZK4LWClient`, with the same configuration
+    ///
+    /// Fails with `ZK4LWError::UnsupportedTransportError` if a `transport`, `proxy` or `tls` is set,
+    /// as the asynchronous client only supports plain TCP connections.
+    #[cfg(feature = "async")]
+    pub fn build_async(self) -> ZK4LWResult<AsyncZK4LWClient> {
+        AsyncZK4LWClient::try_from(self.build())
+    }
+
+    /// Returns the default transport, configured with proxy and TLS (if any)
+    ///
+    /// Returns `None` for the plain TCP transport, that the `AsyncZK4LWClient` also supports.
+    fn default_transport(&self) -> Option<Arc<dyn ZK4LWTransport>> {
+        #[cfg(feature = "tls")]
+        if let Some(tls) = &self.tls {
+            let transport = ZK4LWTlsTransport::new(tls.clone());
+            return Some(Arc::new(match &self.proxy {
+                Some(proxy) => transport.with_proxy(proxy.clone()),
+                None => transport,
+            }));
+        }
+
+        let proxy = self.proxy.clone()?;
+        Some(Arc::new(ZK4LWTcpTransport::new().with_proxy(proxy)))
+    }
+
+    pub fn build(self) -> ZK4LWClient {
+        let transport = self.transport.clone().or_else(|| self.default_transport());
+
+        ZK4LWClient {
+            host: self.host,
+            port: self.port,
+            parse_policy: self.parse_policy,
+            connect_timeout: self.connect_timeout,
+            read_timeout: self.read_timeout,
+            write_timeout: self.write_timeout,
+            deadline: self.deadline,
+            retry_policy: self.retry_policy,
+            transport,
+        }
+    }
+}
+
+/// The Zookeeper "Four Letter Words" client
+#[derive(Debug, Clone)]
+pub struct ZK4LWClient {
+    pub(crate) host: String,
+    pub(crate) port: u16,
+    pub(crate) parse_policy: ZK4LWParsePolicy,
+    pub(crate) connect_timeout: Option<Duration>,
+    pub(crate) read_timeout: Option<Duration>,
+    pub(crate) write_timeout: Option<Duration>,
+    pub(crate) deadline: Option<Duration>,
+    pub(crate) retry_policy: Option<Arc<dyn ZK4LWRetryPolicy>>,
+    pub(crate) transport: Option<Arc<dyn ZK4LWTransport>>,
 }
 
 impl ZK4LWClient {
-    /// Create a new ZK 4LW client
+    /// Create a new ZK 4LW client, without timeouts
     ///
     /// # Arguments
     /// * `host` - host in the ZK Ensemble to send commands to; can be both an IP or a Hostname
     /// * `port` - port to send commands on
     pub fn new<S: Into<String>>(host: S, port: u16) -> Self {
-        Self {
-            host: host.into(),
-            port,
-        }
+        Self::builder().host(host).port(port).build()
+    }
+
+    /// Create a builder, to configure timeouts and other options of the client
+    pub fn builder() -> ZK4LWClientBuilder {
+        ZK4LWClientBuilder::default()
+    }
+
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Set the policy applied when parsing responses (default: `ZK4LWParsePolicy::STRICT`)
+    pub fn with_parse_policy(mut self, parse_policy: ZK4LWParsePolicy) -> Self {
+        self.parse_policy = parse_policy;
+        self
+    }
+
+    pub fn parse_policy(&self) -> ZK4LWParsePolicy {
+        self.parse_policy
+    }
+
+    /// Execute the given command type and return a result containing the response
+    ///
+    /// This is a shortcut for commands that don't carry any argument:
+    /// see `execute_command` for the others.
+    pub fn execute<C: ZK4LWCommand + Default>(&self) -> ZK4LWResult<C::Response> {
+        self.execute_command(&C::default())
     }
 
     /// Execute the given command and return a result containing the response
-    pub fn execute<C: ZK4LWCommand>(&self) -> ZK4LWResult<C::Response>
-    where
-        C: ZK4LWCommand,
-    {
-        // Connect TCP socket to ZooKeeper server
-        let mut stream = net::TcpStream::connect((self.host.as_str(), self.port))?;
+    ///
+    /// # Arguments
+    /// * `command` - the command to execute
+    pub fn execute_command<C: ZK4LWCommand>(&self, command: &C) -> ZK4LWResult<C::Response> {
+        self.execute_command_with_warnings(command)
+            .map(|(response, _)| response)
+    }
+
+    /// Execute the given command type and return a result containing the response,
+    /// together with the warnings collected parsing it (only with `ZK4LWParsePolicy::LENIENT`)
+    pub fn execute_with_warnings<C: ZK4LWCommand + Default>(
+        &self,
+    ) -> ZK4LWResult<(C::Response, Vec<ZK4LWParseWarning>)> {
+        self.execute_command_with_warnings(&C::default())
+    }
+
+    /// Execute the given command and return a result containing the response,
+    /// together with the warnings collected parsing it (only with `ZK4LWParsePolicy::LENIENT`)
+    ///
+    /// # Arguments
+    /// * `command` - the command to execute
+    pub fn execute_command_with_warnings<C: ZK4LWCommand>(
+        &self,
+        command: &C,
+    ) -> ZK4LWResult<(C::Response, Vec<ZK4LWParseWarning>)> {
+        let mut attempt = 1;
+        loop {
+            let mut request_sent = false;
+            let error = match self.execute_command_once(command, &mut request_sent) {
+                Ok(response) => return Ok(response),
+                Err(error) => error,
+            };
+
+            // Retry after a delay, if the policy allows it
+            if C::MUTATING && request_sent {
+                return Err(error);
+            }
+            match self.retry_delay(attempt, &error) {
+                Some(delay) => thread::sleep(delay),
+                None => return Err(error),
+            }
+            attempt += 1;
+        }
+    }
+
+    /// Returns how long to wait before retrying the failed attempt, if the retry policy allows it
+    pub(crate) fn retry_delay(&self, attempt: u32, error: &ZK4LWError) -> Option<Duration> {
+        self.retry_policy
+            .as_ref()
+            .and_then(|policy| policy.retry_delay(attempt, error))
+    }
+
+    /// Execute the given command once, without retrying
+    ///
+    /// `request_sent` is set once the client starts sending the request to the server.
+    fn execute_command_once<C: ZK4LWCommand>(
+        &self,
+        command: &C,
+        request_sent: &mut bool,
+    ) -> ZK4LWResult<(C::Response, Vec<ZK4LWParseWarning>)> {
+        let deadline = self.deadline.map(|d| Instant::now() + d);
+
+        // Open connection to ZooKeeper server
+        let mut connection = self.transport().open(
+            &self.host,
+            self.port,
+            phase_timeout(self.connect_timeout, deadline, ZK4LWPhase::CONNECT)?,
+        )?;
 
         // Send 4LW command
-        stream.write_all(C::request_body().as_bytes())?;
+        let timeout = phase_timeout(self.write_timeout, deadline, ZK4LWPhase::WRITE)?;
+        *request_sent = true;
+        connection.write_request(&command.request_body(), timeout)?;
 
-        // Read the whole response to buffer
+        // Read the whole response to buffer (or until the command deems it complete)
         let mut response_buffer = Vec::new();
-        stream.read_to_end(&mut response_buffer)?;
+        let mut chunk = [0u8; READ_CHUNK_SIZE];
+        while !C::is_response_complete(&response_buffer) {
+            match connection.read_response(
+                &mut chunk,
+                phase_timeout(self.read_timeout, deadline, ZK4LWPhase::READ)?,
+            )? {
+                0 => break,
+                n => response_buffer.extend_from_slice(&chunk[..n]),
+            }
+        }
+
+        build_command_response::<C>(&response_buffer, self.parse_policy)
+    }
+
+    /// Transport the client sends commands over
+    fn transport(&self) -> &dyn ZK4LWTransport {
+        match &self.transport {
+            Some(transport) => transport.as_ref(),
+            None => &DEFAULT_TRANSPORT,
+        }
+    }
+
+    /// Probe the server (via the Server command) and return its capabilities
+    ///
+    /// The Server command is used as it's supported by every version of Zookeeper,
+    /// and it's the only one in the default commands whitelist (since ZK 3.5.3).
+    pub fn capabilities(&self) -> ZK4LWResult<ZK4LWCapabilities> {
+        let srvr = self.execute::<ZK4LWServer>()?;
+        Ok(ZK4LWCapabilities::for_version(srvr.version))
+    }
+
+    /// Execute the given command type, that only the leader can answer (e.g. `ZK4LWDump`),
+    /// after checking the state of the server (via the Server command)
+    ///
+    /// If the server doesn't track sessions (see `ZK4LWServerState::tracks_sessions()`), the command
+    /// is not sent, and this fails with a `ZK4LWError::NotLeaderError` reporting the state.
+    pub fn execute_leader_only<C: ZK4LWCommand + Default>(&self) -> ZK4LWResult<C::Response> {
+        let srvr = self.execute::<ZK4LWServer>()?;
+        if !srvr.server_state.tracks_sessions() {
+            return Err(ZK4LWError::NotLeaderError(C::NAME, Some(srvr.server_state)));
+        }
+
+        self.execute::<C>()
+    }
+}
+
+/// Builds the response of the command from the raw bytes received, applying the given parse policy
+pub(crate) fn build_command_response<C: ZK4LWCommand>(
+    response_buffer: &[u8],
+    parse_policy: ZK4LWParsePolicy,
+) -> ZK4LWResult<(C::Response, Vec<ZK4LWParseWarning>)> {
+    // Convert buffer to &str
+    let response_body = str::from_utf8(response_buffer)?;
+
+    // Refuse responses that are not produced by the command itself
+    check_response_body::<C>(response_body)?;
+
+    // Produce final response
+    let mut parser = ZK4LWParser::new(parse_policy);
+    let response = C::build_response_with_parser(response_body, &mut parser)?;
+
+    Ok((response, parser.into_warnings()))
+}
+
+/// Returns the timeout to apply to the given phase: the shortest between its own
+/// and the time left before the deadline (if any)
+///
+/// Fails with `ZK4LWError::Timeout` if the deadline has already passed.
+pub(crate) fn phase_timeout(
+    timeout: Option<Duration>,
+    deadline: Option<Instant>,
+    phase: ZK4LWPhase,
+) -> ZK4LWResult<Option<Duration>> {
+    let remaining = match deadline {
+        Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+            Some(remaining) if remaining > Duration::from_millis(0) => Some(remaining),
+            _ => return Err(ZK4LWError::Timeout(phase)),
+        },
+        None => None,
+    };
+
+    Ok(match (timeout, remaining) {
+        (Some(timeout), Some(remaining)) => Some(timeout.min(remaining)),
+        (timeout, remaining) => timeout.or(remaining),
+    })
+}
+
+/// Converts an I/O error encountered in the given phase, recognising timeouts
+pub(crate) fn phase_error(error: io::Error, phase: ZK4LWPhase) -> ZK4LWError {
+    match error.kind() {
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => ZK4LWError::Timeout(phase),
+        _ => error.into(),
+    }
+}
+
+/// Checks the response body is not one of the sentences that the server sends in place of
+/// the output of any command (e.g. because the command is not whitelisted)
+fn check_response_body<C: ZK4LWCommand>(response_body: &str) -> ZK4LWResult<()> {
+    let response_body = response_body.trim();
+    if response_body.starts_with(C::NAME)
+        && response_body.ends_with(RESPONSE_NOT_WHITELISTED_SUFFIX)
+    {
+        return Err(ZK4LWError::NotWhitelistedError(C::NAME));
+    }
+    if response_body == RESPONSE_NOT_SERVING {
+        return Err(ZK4LWError::NotServingError);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::{Read, Write},
+        net, thread,
+        time::{Duration, Instant},
+    };
+
+    use crate::client::ZK4LWClient;
+    use crate::commands::dump::ZK4LWDump;
+    use crate::commands::mntr::ZK4LWMonitor;
+    use crate::commands::reset::ZK4LWResetStats;
+    use crate::commands::ruok::{ZK4LWRuok, ZK4LWRuokResponse};
+    use crate::errors::{ZK4LWError, ZK4LWPhase};
+    use crate::policy::ZK4LWParsePolicy;
+    use crate::retry::ZK4LWBackoffRetryPolicy;
+    use crate::state::ZK4LWServerState::FOLLOWER;
+    use crate::transport::memory::ZK4LWMemoryTransport;
+
+    /// Spawns a server that answers a single request with the given response, then closes the connection
+    fn serve_once(response: &'static str) -> (u16, thread::JoinHandle<()>) {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 4];
+            stream.read_exact(&mut request).unwrap();
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+
+        (port, server)
+    }
+
+    #[test]
+    fn should_stop_reading_once_response_is_complete() {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // Server that answers, but never closes the connection (until the client does)
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 4];
+            stream.read_exact(&mut request).unwrap();
+            assert_eq!(&request, b"ruok");
+            stream.write_all(b"imok").unwrap();
+            let mut rest = Vec::new();
+            stream.read_to_end(&mut rest).unwrap();
+        });
+
+        let client = ZK4LWClient::new("127.0.0.1", port);
+        assert_eq!(
+            client.execute::<ZK4LWRuok>().unwrap(),
+            ZK4LWRuokResponse::IMOK
+        );
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn should_fail_if_command_is_not_whitelisted() {
+        let (port, server) =
+            serve_once("mntr is not executed because it is not in the whitelist.\n");
+        match ZK4LWClient::new("127.0.0.1", port).execute::<ZK4LWMonitor>() {
+            Err(ZK4LWError::NotWhitelistedError(command)) => assert_eq!(command, "mntr"),
+            _ => panic!("Expected NotWhitelistedError"),
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn should_fail_if_server_is_not_serving() {
+        let (port, server) =
+            serve_once("This ZooKeeper instance is not currently serving requests\n");
+        match ZK4LWClient::new("127.0.0.1", port).execute::<ZK4LWMonitor>() {
+            Err(ZK4LWError::NotServingError) => {}
+            _ => panic!("Expected NotServingError"),
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn should_apply_parse_policy_of_the_client() {
+        const RESPONSE: &str = "zk_version\t3.4.14-4c25d480e66aadd371de8bd2fd8da255ac140bcf, built on 03/06/2019 16:18 GMT\nzk_znode_count\tmany\n";
+
+        let (port, server) = serve_once(RESPONSE);
+        match ZK4LWClient::new("127.0.0.1", port).execute::<ZK4LWMonitor>() {
+            Err(ZK4LWError::MissingFieldError(_)) => {}
+            _ => panic!("Expected MissingFieldError"),
+        }
+        server.join().unwrap();
+
+        let (port, server) = serve_once(RESPONSE);
+        let client =
+            ZK4LWClient::new("127.0.0.1", port).with_parse_policy(ZK4LWParsePolicy::LENIENT);
+        let (mntr_resp, warnings) = client.execute_with_warnings::<ZK4LWMonitor>().unwrap();
+        assert_eq!(mntr_resp.version.to_string(), "3.4.14");
+        assert_eq!(mntr_resp.znode_count, 0);
+        assert!(warnings.iter().any(|w| w.field == "zk_znode_count"));
+        assert!(warnings.iter().any(|w| w.field == "zk_packets_sent"));
+        server.join().unwrap();
+    }
 
-        // Convert buffer to &str
-        let response_body = str::from_utf8(&response_buffer)?;
+    #[test]
+    fn should_time_out_if_server_does_not_respond() {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // Server that accepts connections, but never responds
+        let server = thread::spawn(move || {
+            for _ in 0..2 {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut rest = Vec::new();
+                let _ = stream.read_to_end(&mut rest);
+            }
+        });
+
+        let client = ZK4LWClient::builder()
+            .host("127.0.0.1")
+            .port(port)
+            .connect_timeout(Duration::from_secs(5))
+            .read_timeout(Duration::from_millis(100))
+            .build();
+        match client.execute::<ZK4LWMonitor>() {
+            Err(ZK4LWError::Timeout(phase)) => assert_eq!(phase, ZK4LWPhase::READ),
+            _ => panic!("Expected Timeout"),
+        }
+
+        // The deadline bounds the whole execution, even if the read timeout is longer
+        let client = ZK4LWClient::builder()
+            .host("127.0.0.1")
+            .port(port)
+            .read_timeout(Duration::from_secs(30))
+            .deadline(Duration::from_millis(200))
+            .build();
+        let start = Instant::now();
+        match client.execute::<ZK4LWMonitor>() {
+            Err(ZK4LWError::Timeout(phase)) => assert_eq!(phase, ZK4LWPhase::READ),
+            _ => panic!("Expected Timeout"),
+        }
+        assert!(start.elapsed() < Duration::from_secs(5));
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn should_retry_according_to_retry_policy() {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        // Server that is not serving requests at first
+        let server = thread::spawn(move || {
+            for response in ["This ZooKeeper instance is not currently serving requests\n"; 2]
+                .iter()
+                .chain(["imok"].iter())
+            {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0u8; 4];
+                stream.read_exact(&mut request).unwrap();
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        let client = ZK4LWClient::builder()
+            .host("127.0.0.1")
+            .port(port)
+            .retry_policy(ZK4LWBackoffRetryPolicy {
+                initial_delay: Duration::from_millis(10),
+                ..ZK4LWBackoffRetryPolicy::new(3)
+            })
+            .build();
+        assert_eq!(
+            client.execute::<ZK4LWRuok>().unwrap(),
+            ZK4LWRuokResponse::IMOK
+        );
+        server.join().unwrap();
+
+        // Without retries, the first failure is returned
+        let (port, server) =
+            serve_once("This ZooKeeper instance is not currently serving requests\n");
+        match ZK4LWClient::new("127.0.0.1", port).execute::<ZK4LWRuok>() {
+            Err(ZK4LWError::NotServingError) => {}
+            _ => panic!("Expected NotServingError"),
+        }
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn should_not_retry_mutating_commands_once_sent() {
+        let not_serving = "This ZooKeeper instance is not currently serving requests\n";
+        let transport = ZK4LWMemoryTransport::new()
+            .with_response("ruok", not_serving)
+            .with_response("srst", not_serving);
+        let client = ZK4LWClient::builder()
+            .transport(transport.clone())
+            .retry_policy(ZK4LWBackoffRetryPolicy {
+                initial_delay: Duration::from_millis(1),
+                ..ZK4LWBackoffRetryPolicy::new(3)
+            })
+            .build();
+
+        match client.execute::<ZK4LWRuok>() {
+            Err(ZK4LWError::NotServingError) => {}
+            _ => panic!("Expected NotServingError"),
+        }
+        assert_eq!(transport.requests().len(), 3);
+
+        match client.execute::<ZK4LWResetStats>() {
+            Err(ZK4LWError::NotServingError) => {}
+            _ => panic!("Expected NotServingError"),
+        }
+        assert_eq!(transport.requests().len(), 4);
+    }
+
+    #[test]
+    fn should_check_server_state_before_executing_leader_only_commands() {
+        let srvr_resp_body = fs::read_to_string("../../fixtures/3.6/srvr.response").unwrap();
+        let dump_resp_body = fs::read_to_string("../../fixtures/3.6/dump.response").unwrap();
+
+        let transport = ZK4LWMemoryTransport::new()
+            .with_response(
+                "srvr",
+                srvr_resp_body.replace("Mode: leader", "Mode: follower"),
+            )
+            .with_response("dump", dump_resp_body.as_str());
+        let client = ZK4LWClient::builder().transport(transport.clone()).build();
+        match client.execute_leader_only::<ZK4LWDump>() {
+            Err(ZK4LWError::NotLeaderError(command, Some(state))) => {
+                assert_eq!(command, "dump");
+                assert_eq!(state, FOLLOWER);
+            }
+            _ => panic!("Expected NotLeaderError"),
+        }
+        assert_eq!(transport.requests(), vec![b"srvr".to_vec()]);
 
-        // Produce final response
-        C::build_response(response_body)
+        let transport = ZK4LWMemoryTransport::new()
+            .with_response("srvr", srvr_resp_body)
+            .with_response("dump", dump_resp_body);
+        let client = ZK4LWClient::builder().transport(transport).build();
+        assert!(client.execute_leader_only::<ZK4LWDump>().is_ok());
     }
 }
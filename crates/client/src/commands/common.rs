@@ -1,6 +1,6 @@
 //! 4LW common values/types returned by commands
 
-use std::default::Default;
+use std::{collections::HashMap, default::Default};
 
 /// Sample of Time-related Metric, as returned by ZooKeeper
 ///
@@ -9,6 +9,7 @@ use std::default::Default;
 ///
 /// Not all fields are present, but it depends on the specific ZooKeeper version.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZK4LWTimeMetricSample {
     /// Average
     pub avg: f64,
@@ -39,6 +40,11 @@ impl ZK4LWTimeMetricSample {
             ..Default::default()
         }
     }
+
+    /// Returns `true` if at least one percentile was reported
+    pub fn has_percentiles(&self) -> bool {
+        self.p50.is_some() || self.p95.is_some() || self.p99.is_some() || self.p999.is_some()
+    }
 }
 
 impl Default for ZK4LWTimeMetricSample {
@@ -64,6 +70,7 @@ impl Default for ZK4LWTimeMetricSample {
 ///
 /// Not all fields are present, but it depends on the specific ZooKeeper version.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZK4LWMetricSample {
     /// Average
     pub avg: f64,
@@ -99,3 +106,43 @@ impl Default for ZK4LWMetricSample {
         }
     }
 }
+
+/// Discards the percentiles
+impl From<ZK4LWTimeMetricSample> for ZK4LWMetricSample {
+    fn from(time_sample: ZK4LWTimeMetricSample) -> Self {
+        ZK4LWMetricSample {
+            avg: time_sample.avg,
+            max: time_sample.max,
+            min: time_sample.min,
+            count: time_sample.count,
+            sum: time_sample.sum,
+        }
+    }
+}
+
+/// "Summary" Metrics, as returned by ZooKeeper, by name
+///
+/// A summary metric is reported as a family of values, one per statistic
+/// (i.e. `zk_<stat>_<name>`, like `zk_avg_fsynctime` or `zk_p99_readlatency`):
+/// the family is reassembled into a `ZK4LWTimeMetricSample` if it reports percentiles,
+/// a `ZK4LWMetricSample` otherwise.
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWSummaryMetrics {
+    /// Summary metrics without percentiles
+    pub samples: HashMap<String, ZK4LWMetricSample>,
+    /// Summary metrics with percentiles
+    pub time_samples: HashMap<String, ZK4LWTimeMetricSample>,
+}
+
+impl ZK4LWSummaryMetrics {
+    /// Returns `true` if there are no summary metrics
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty() && self.time_samples.is_empty()
+    }
+
+    /// Returns the number of summary metrics
+    pub fn len(&self) -> usize {
+        self.samples.len() + self.time_samples.len()
+    }
+}
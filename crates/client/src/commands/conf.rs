@@ -0,0 +1,400 @@
+//! The 4LW Configuration command. Also known as "conf".
+//!
+//! This command outputs the details about the serving configuration.
+//! Since ZooKeeper 3.5.0 this includes the (dynamic) ensemble membership.
+//!
+//! Available since: ZooKeeper 3.3.0
+
+use std::{collections::HashMap, fmt, path::PathBuf, str};
+
+use crate::{client::*, errors::*, parsing::*, policy::*, result::*, zxid::*};
+
+const COMMAND: &str = "conf";
+
+/// Fields always present in the response (i.e. enforced by `ZK4LWParsePolicy::STRICT`)
+///
+/// NOTE: quorum fields (e.g. `electionPort`) are not required, as they are only reported by ensembles.
+const REQUIRED_FIELDS: &[&str] = &[
+    "clientPort",
+    "dataDir",
+    "dataLogDir",
+    "tickTime",
+    "maxClientCnxns",
+    "minSessionTimeout",
+    "maxSessionTimeout",
+    "serverId",
+];
+
+const MEMBER_KEY_PREFIX: &str = "server.";
+const MEMBER_CLIENT_ADDRESS_SEPARATOR: char = ';';
+const MEMBER_ADDRESS_SEPARATOR: char = ':';
+
+const PEER_TYPE_PARTICIPANT: &str = "participant";
+const PEER_TYPE_OBSERVER: &str = "observer";
+const PEER_TYPE_PARTICIPANT_ORDINAL: &str = "0";
+const PEER_TYPE_OBSERVER_ORDINAL: &str = "1";
+
+/// Type of a peer in the ensemble, as configured
+///
+/// It's reported both as the `peerType` of the server (as a number),
+/// and as the role of each of the members (as a string).
+#[derive(PartialEq, Default, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum ZK4LWPeerType {
+    #[default]
+    PARTICIPANT,
+    OBSERVER,
+}
+
+impl str::FromStr for ZK4LWPeerType {
+    type Err = ZK4LWError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            PEER_TYPE_PARTICIPANT | PEER_TYPE_PARTICIPANT_ORDINAL => Ok(ZK4LWPeerType::PARTICIPANT),
+            PEER_TYPE_OBSERVER | PEER_TYPE_OBSERVER_ORDINAL => Ok(ZK4LWPeerType::OBSERVER),
+            _ => Err(ZK4LWError::ParseStringError(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Debug for ZK4LWPeerType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self {
+            ZK4LWPeerType::PARTICIPANT => write!(f, "{}", PEER_TYPE_PARTICIPANT),
+            ZK4LWPeerType::OBSERVER => write!(f, "{}", PEER_TYPE_OBSERVER),
+        }
+    }
+}
+
+/// Member of the ensemble, as listed in the `membership:` section of the response
+///
+/// Each member is reported in the form `server.<id>=<host>:<quorum port>:<election port>[:<role>][;[<client address>:]<client port>]`.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWConfMember {
+    pub id: i64,
+    pub host: String,
+    pub quorum_port: i64,
+    pub election_port: i64,
+    pub role: ZK4LWPeerType,
+    pub client_address: Option<String>,
+    pub client_port: Option<i64>,
+}
+
+impl str::FromStr for ZK4LWConfMember {
+    type Err = ZK4LWError;
+
+    /// Parses a member from a `server.<id>=<address>` line
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_err =
+            || ZK4LWError::ParseStringError(format!("Unable to parse member from string: '{}'", s));
+
+        let mut key_val = s.splitn(2, '=');
+        let id = key_val
+            .next()
+            .and_then(|k| k.trim().strip_prefix(MEMBER_KEY_PREFIX))
+            .ok_or_else(parse_err)?
+            .parse()?;
+        let address = key_val.next().ok_or_else(parse_err)?.trim();
+
+        // Split the "server" part from the (optional) "client" part
+        let mut address_split = address.splitn(2, MEMBER_CLIENT_ADDRESS_SEPARATOR);
+        let server_address = address_split.next().ok_or_else(parse_err)?;
+        let client_address = address_split.next();
+
+        // NOTE: The host can contain the separator too (i.e. IPv6), so we parse from the right
+        let mut server_split: Vec<&str> = server_address
+            .rsplitn(4, MEMBER_ADDRESS_SEPARATOR)
+            .collect();
+        let role = match server_split.first().map(|r| r.parse::<ZK4LWPeerType>()) {
+            Some(Ok(role)) if server_split.len() == 4 => {
+                server_split.remove(0);
+                role
+            }
+            _ => {
+                // Role is optional: without it, the last 3 parts are "election port", "quorum port" and "host"
+                server_split = server_address
+                    .rsplitn(3, MEMBER_ADDRESS_SEPARATOR)
+                    .collect();
+                ZK4LWPeerType::default()
+            }
+        };
+        if server_split.len() != 3 {
+            return Err(parse_err());
+        }
+
+        let (client_address, client_port) = match client_address.map(str::trim) {
+            Some(client) if !client.is_empty() => {
+                match client.rsplit_once(MEMBER_ADDRESS_SEPARATOR) {
+                    Some((address, port)) => (Some(address.to_string()), Some(port.parse()?)),
+                    None => (None, Some(client.parse()?)),
+                }
+            }
+            _ => (None, None),
+        };
+
+        Ok(ZK4LWConfMember {
+            id,
+            host: server_split[2].to_string(),
+            quorum_port: server_split[1].parse()?,
+            election_port: server_split[0].parse()?,
+            role,
+            client_address,
+            client_port,
+        })
+    }
+}
+
+/// Response to the `conf` command
+///
+/// The fields are "divided" into 3 "classes":
+///
+/// * mapped & always present: they will always be in the response,
+///   and the value will always be valid
+/// * mapped & sometimes present: they will always be in the response,
+///   but their value is an `Option` that will be set to `None` if missing -
+///   a value can be missing because the value is not reported by the
+///   specific instance of ZooKeeper against which the command was executed
+/// * unmapped: when a value hasn't been mapped (yet), its stored in the
+///   `misc` field, that is an hash-map - the idea is that we then release
+///   an update that maps it
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWConfResponse {
+    // ports
+    pub client_port: i64,
+    pub secure_client_port: Option<i64>,
+    pub client_port_listen_backlog: Option<i64>,
+    pub election_port: i64,
+    pub quorum_port: i64,
+    // directories
+    pub data_dir: PathBuf,
+    pub data_dir_size: Option<i64>,
+    pub data_log_dir: PathBuf,
+    pub data_log_size: Option<i64>,
+    // timing
+    pub tick_time: i64,
+    pub init_limit: i64,
+    pub sync_limit: i64,
+    // sessions
+    pub min_session_timeout: i64,
+    pub max_session_timeout: i64,
+    // connections
+    pub max_client_cnxns: i64,
+    // server
+    pub server_id: i64,
+    pub election_alg: i64,
+    pub peer_type: ZK4LWPeerType,
+    // membership
+    /// Members of the ensemble, sorted by `id`
+    /// NOTE: only reported by ZK >= 3.5.x, that introduced dynamic reconfiguration.
+    pub members: Vec<ZK4LWConfMember>,
+    /// Version of the (dynamic) configuration of the ensemble
+    pub config_version: Option<ZK4LWZxid>,
+    // unknown/unmapped fields
+    pub misc: HashMap<String, String>,
+}
+
+impl ZK4LWConfResponse {
+    /// Maps a field of the response, by key
+    fn map_field(&mut self, key: &str, val: &str) -> ZK4LWResult<()> {
+        match key {
+            // ports
+            "clientPort" => self.client_port = val.parse()?,
+            "secureClientPort" => self.secure_client_port = Some(val.parse()?),
+            "clientPortListenBacklog" => self.client_port_listen_backlog = Some(val.parse()?),
+            "electionPort" => self.election_port = val.parse()?,
+            "quorumPort" => self.quorum_port = val.parse()?,
+            // directories
+            "dataDir" => self.data_dir = val.into(),
+            "dataDirSize" => self.data_dir_size = Some(val.parse()?),
+            "dataLogDir" => self.data_log_dir = val.into(),
+            "dataLogSize" => self.data_log_size = Some(val.parse()?),
+            // timing
+            "tickTime" => self.tick_time = val.parse()?,
+            "initLimit" => self.init_limit = val.parse()?,
+            "syncLimit" => self.sync_limit = val.parse()?,
+            // sessions
+            "minSessionTimeout" => self.min_session_timeout = val.parse()?,
+            "maxSessionTimeout" => self.max_session_timeout = val.parse()?,
+            // connections
+            "maxClientCnxns" => self.max_client_cnxns = val.parse()?,
+            // server
+            "serverId" => self.server_id = val.parse()?,
+            "electionAlg" => self.election_alg = val.parse()?,
+            "peerType" => self.peer_type = val.parse()?,
+            // membership
+            // NOTE: the configuration version is reported in hexadecimal (without `0x` prefix)
+            "version" => self.config_version = Some(val.parse()?),
+            k if k.starts_with(MEMBER_KEY_PREFIX) => {
+                self.members.push(format!("{}={}", key, val).parse()?)
+            }
+            _ => {
+                self.misc.insert(key.into(), val.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The Configuration (i.e. "conf") command
+#[derive(Default)]
+pub struct ZK4LWConf;
+
+impl ZK4LWCommand for ZK4LWConf {
+    type Response = ZK4LWConfResponse;
+
+    const NAME: &'static str = COMMAND;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        Self::build_response_with_parser(response_body, &mut ZK4LWParser::default())
+    }
+
+    fn build_response_with_parser(
+        response_body: &str,
+        parser: &mut ZK4LWParser,
+    ) -> ZK4LWResult<Self::Response> {
+        // Parse response body into key/value pairs
+        let response_map = equal_separated_bytes_to_key_value(response_body)?;
+        parser.require_fields(&response_map, REQUIRED_FIELDS)?;
+
+        // Map by key to a specific field in the response
+        let mut response = ZK4LWConfResponse::default();
+        for (key, val) in response_map.into_iter() {
+            parser.field(key, response.map_field(key, val))?;
+        }
+        response.members.sort_by_key(|m| m.id);
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::client::ZK4LWCommand;
+    use crate::commands::conf::{ZK4LWConf, ZK4LWConfMember, ZK4LWPeerType};
+    use crate::zxid::ZK4LWZxid;
+
+    #[test]
+    fn should_build_response_from_zk34_conf_response_body() {
+        let conf_34_resp_body = fs::read_to_string("../../fixtures/3.4/conf.response").unwrap();
+        let conf_34_resp = ZK4LWConf::build_response(conf_34_resp_body.as_str()).unwrap();
+
+        assert_eq!(conf_34_resp.client_port, 2181);
+        assert_eq!(conf_34_resp.secure_client_port, None);
+        assert_eq!(conf_34_resp.client_port_listen_backlog, None);
+        assert_eq!(conf_34_resp.election_port, 3888);
+        assert_eq!(conf_34_resp.quorum_port, 2888);
+        assert_eq!(conf_34_resp.data_dir, PathBuf::from("/data/version-2"));
+        assert_eq!(conf_34_resp.data_dir_size, None);
+        assert_eq!(
+            conf_34_resp.data_log_dir,
+            PathBuf::from("/datalog/version-2")
+        );
+        assert_eq!(conf_34_resp.data_log_size, None);
+        assert_eq!(conf_34_resp.tick_time, 2000);
+        assert_eq!(conf_34_resp.init_limit, 5);
+        assert_eq!(conf_34_resp.sync_limit, 2);
+        assert_eq!(conf_34_resp.min_session_timeout, 4000);
+        assert_eq!(conf_34_resp.max_session_timeout, 40000);
+        assert_eq!(conf_34_resp.max_client_cnxns, 60);
+        assert_eq!(conf_34_resp.server_id, 30);
+        assert_eq!(conf_34_resp.election_alg, 3);
+        assert_eq!(conf_34_resp.peer_type, ZK4LWPeerType::PARTICIPANT);
+        assert_eq!(conf_34_resp.members.len(), 0);
+        assert_eq!(conf_34_resp.config_version, None);
+        assert_eq!(conf_34_resp.misc.len(), 0);
+    }
+
+    #[test]
+    fn should_build_response_from_zk35_conf_response_body() {
+        let conf_35_resp_body = fs::read_to_string("../../fixtures/3.5/conf.response").unwrap();
+        let conf_35_resp = ZK4LWConf::build_response(conf_35_resp_body.as_str()).unwrap();
+
+        assert_eq!(conf_35_resp.client_port, 2181);
+        assert_eq!(conf_35_resp.secure_client_port, Some(-1));
+        assert_eq!(conf_35_resp.client_port_listen_backlog, None);
+        assert_eq!(conf_35_resp.data_dir, PathBuf::from("/data/version-2"));
+        assert_eq!(conf_35_resp.data_dir_size, Some(0));
+        assert_eq!(
+            conf_35_resp.data_log_dir,
+            PathBuf::from("/datalog/version-2")
+        );
+        assert_eq!(conf_35_resp.data_log_size, Some(679));
+        assert_eq!(conf_35_resp.server_id, 30);
+        assert_eq!(conf_35_resp.peer_type, ZK4LWPeerType::PARTICIPANT);
+        assert_eq!(conf_35_resp.config_version, Some(ZK4LWZxid(0)));
+        assert_eq!(
+            conf_35_resp
+                .members
+                .iter()
+                .map(|m| m.id)
+                .collect::<Vec<i64>>(),
+            vec![10, 20, 30, 41, 51]
+        );
+        assert_eq!(
+            conf_35_resp.members[0],
+            ZK4LWConfMember {
+                id: 10,
+                host: "zk10".into(),
+                quorum_port: 2888,
+                election_port: 3888,
+                role: ZK4LWPeerType::PARTICIPANT,
+                client_address: Some("0.0.0.0".into()),
+                client_port: Some(2181),
+            }
+        );
+        assert_eq!(conf_35_resp.members[4].host, "zk51");
+        assert_eq!(conf_35_resp.members[4].role, ZK4LWPeerType::OBSERVER);
+        assert_eq!(conf_35_resp.misc.len(), 0);
+    }
+
+    #[test]
+    fn should_build_response_from_zk36_conf_response_body() {
+        let conf_36_resp_body = fs::read_to_string("../../fixtures/3.6/conf.response").unwrap();
+        let conf_36_resp = ZK4LWConf::build_response(conf_36_resp_body.as_str()).unwrap();
+
+        assert_eq!(conf_36_resp.client_port, 2181);
+        assert_eq!(conf_36_resp.secure_client_port, Some(-1));
+        assert_eq!(conf_36_resp.client_port_listen_backlog, Some(-1));
+        assert_eq!(conf_36_resp.data_dir_size, Some(0));
+        assert_eq!(conf_36_resp.data_log_size, Some(712));
+        assert_eq!(conf_36_resp.tick_time, 2000);
+        assert_eq!(conf_36_resp.server_id, 30);
+        assert_eq!(conf_36_resp.config_version, Some(ZK4LWZxid(0)));
+        assert_eq!(conf_36_resp.members.len(), 5);
+        assert_eq!(
+            conf_36_resp
+                .members
+                .iter()
+                .filter(|m| m.role == ZK4LWPeerType::OBSERVER)
+                .count(),
+            2
+        );
+        assert_eq!(conf_36_resp.misc.len(), 0);
+    }
+
+    #[test]
+    fn should_parse_conf_member() {
+        let member: ZK4LWConfMember = "server.1=[::1]:2888:3888;2181".parse().unwrap();
+        assert_eq!(member.id, 1);
+        assert_eq!(member.host, "[::1]");
+        assert_eq!(member.quorum_port, 2888);
+        assert_eq!(member.election_port, 3888);
+        assert_eq!(member.role, ZK4LWPeerType::PARTICIPANT);
+        assert_eq!(member.client_address, None);
+        assert_eq!(member.client_port, Some(2181));
+
+        let member: ZK4LWConfMember = "server.2=zk2:2888:3888:observer".parse().unwrap();
+        assert_eq!(member.host, "zk2");
+        assert_eq!(member.role, ZK4LWPeerType::OBSERVER);
+        assert_eq!(member.client_port, None);
+
+        assert!("server.3=zk3:2888".parse::<ZK4LWConfMember>().is_err());
+    }
+}
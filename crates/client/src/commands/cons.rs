@@ -0,0 +1,299 @@
+//! The 4LW Connections command. Also known as "cons".
+//!
+//! This command lists full connection/session details for all clients connected to this server.
+//! It includes information on numbers of packets received/sent, session id, operation latencies,
+//! last operation performed, etc.
+//!
+//! Available since: ZooKeeper 3.3.0
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    time::{Duration, SystemTime},
+};
+
+use crate::{client::*, parsing::*, policy::*, result::*, zxid::*};
+
+const COMMAND: &str = "cons";
+
+/// Connection of a client to the server, as listed in the response
+///
+/// The session-related fields are set to `None` for connections that haven't established a
+/// session (yet): this is always the case for the connection used to send the `cons` command itself.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWConnection {
+    pub address: IpAddr,
+    pub port: u16,
+    pub interest_ops: i64,
+    // packets
+    pub queued: i64,
+    pub received: i64,
+    pub sent: i64,
+    // session
+    pub session_id: Option<u64>,
+    pub session_timeout: Option<Duration>,
+    pub established: Option<SystemTime>,
+    // last operation
+    pub last_operation: Option<String>,
+    pub last_cxid: Option<u64>,
+    pub last_zxid: Option<ZK4LWZxid>,
+    pub last_response: Option<SystemTime>,
+    // latency
+    pub last_latency: Option<Duration>,
+    pub min_latency: Option<Duration>,
+    pub avg_latency: Option<Duration>,
+    pub max_latency: Option<Duration>,
+    // unknown/unmapped fields
+    pub misc: HashMap<String, String>,
+}
+
+impl ZK4LWConnection {
+    /// Maps a statistic of the connection, by key
+    fn map_field(&mut self, key: &str, val: &str) -> ZK4LWResult<()> {
+        match key {
+            // packets
+            "queued" => self.queued = val.parse()?,
+            "recved" => self.received = val.parse()?,
+            "sent" => self.sent = val.parse()?,
+            // session
+            "sid" => self.session_id = Some(hex_to_u64(val)?),
+            "to" => self.session_timeout = Some(millis_to_duration(val)?),
+            "est" => self.established = Some(millis_to_system_time(val)?),
+            // last operation
+            "lop" => self.last_operation = Some(val.into()),
+            "lcxid" => self.last_cxid = Some(hex_to_u64(val)?),
+            "lzxid" => self.last_zxid = Some(val.parse()?),
+            "lresp" => self.last_response = Some(millis_to_system_time(val)?),
+            // latency
+            "llat" => self.last_latency = Some(millis_to_duration(val)?),
+            "minlat" => self.min_latency = Some(millis_to_duration(val)?),
+            "avglat" => self.avg_latency = Some(millis_to_duration(val)?),
+            "maxlat" => self.max_latency = Some(millis_to_duration(val)?),
+            _ => {
+                self.misc.insert(key.into(), val.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Response to the `cons` command
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWConnectionsResponse {
+    pub connections: Vec<ZK4LWConnection>,
+}
+
+/// The Connections (i.e. "cons") command
+#[derive(Default)]
+pub struct ZK4LWConnections;
+
+impl ZK4LWCommand for ZK4LWConnections {
+    type Response = ZK4LWConnectionsResponse;
+
+    const NAME: &'static str = COMMAND;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        Self::build_response_with_parser(response_body, &mut ZK4LWParser::default())
+    }
+
+    fn build_response_with_parser(
+        response_body: &str,
+        parser: &mut ZK4LWParser,
+    ) -> ZK4LWResult<Self::Response> {
+        let mut response = ZK4LWConnectionsResponse::default();
+
+        for line in response_body.lines().filter(|l| !l.trim().is_empty()) {
+            // Connections that fail to parse are skipped, if the parser is lenient
+            let (address, interest_ops, stats) = match connection_line_to_parts(line) {
+                Ok(parts) => parts,
+                Err(error) => {
+                    parser.field(line.trim(), Err(error))?;
+                    continue;
+                }
+            };
+
+            // Map by key to a specific field in the connection
+            let mut connection = ZK4LWConnection {
+                address: address.ip(),
+                port: address.port(),
+                interest_ops,
+                queued: 0,
+                received: 0,
+                sent: 0,
+                session_id: None,
+                session_timeout: None,
+                established: None,
+                last_operation: None,
+                last_cxid: None,
+                last_zxid: None,
+                last_response: None,
+                last_latency: None,
+                min_latency: None,
+                avg_latency: None,
+                max_latency: None,
+                misc: HashMap::new(),
+            };
+            for (key, val) in stats.into_iter() {
+                parser.field(key, connection.map_field(key, val))?;
+            }
+
+            response.connections.push(connection);
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        net::IpAddr,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use crate::client::ZK4LWCommand;
+    use crate::commands::cons::ZK4LWConnections;
+    use crate::policy::{ZK4LWParsePolicy, ZK4LWParser};
+    use crate::zxid::ZK4LWZxid;
+
+    #[test]
+    fn should_build_response_from_zk34_connections_response_body() {
+        let cons_34_resp_body = fs::read_to_string("../../fixtures/3.4/cons.response").unwrap();
+        let cons_34_resp = ZK4LWConnections::build_response(cons_34_resp_body.as_str()).unwrap();
+
+        assert_eq!(cons_34_resp.connections.len(), 2);
+
+        let conn = &cons_34_resp.connections[0];
+        assert_eq!(conn.address, "172.18.0.7".parse::<IpAddr>().unwrap());
+        assert_eq!(conn.port, 41816);
+        assert_eq!(conn.interest_ops, 1);
+        assert_eq!(conn.queued, 0);
+        assert_eq!(conn.received, 152);
+        assert_eq!(conn.sent, 153);
+        assert_eq!(conn.session_id, Some(0x1e0000c3e670000));
+        assert_eq!(conn.session_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(
+            conn.established,
+            Some(UNIX_EPOCH + Duration::from_millis(1596729380811))
+        );
+        assert_eq!(conn.last_operation.as_deref(), Some("PING"));
+        assert_eq!(conn.last_cxid, Some(0x5));
+        assert_eq!(conn.last_zxid, Some(ZK4LWZxid(u64::MAX)));
+        assert_eq!(
+            conn.last_response,
+            Some(UNIX_EPOCH + Duration::from_millis(1596729431234))
+        );
+        assert_eq!(conn.last_latency, Some(Duration::from_millis(0)));
+        assert_eq!(conn.min_latency, Some(Duration::from_millis(0)));
+        assert_eq!(conn.avg_latency, Some(Duration::from_millis(0)));
+        assert_eq!(conn.max_latency, Some(Duration::from_millis(4)));
+        assert_eq!(conn.misc.len(), 0);
+
+        let conn = &cons_34_resp.connections[1];
+        assert_eq!(conn.port, 59160);
+        assert_eq!(conn.received, 1);
+        assert_eq!(conn.session_id, None);
+        assert_eq!(conn.last_operation, None);
+        assert_eq!(conn.max_latency, None);
+    }
+
+    #[test]
+    fn should_keep_going_on_bad_connections_if_lenient() {
+        let cons_34_resp_body = fs::read_to_string("../../fixtures/3.4/cons.response")
+            .unwrap()
+            .replace("recved=152,", "recved=many,")
+            .replace("/172.18.0.1:59160", "/172.18.0.1:port");
+
+        assert!(ZK4LWConnections::build_response(cons_34_resp_body.as_str()).is_err());
+
+        let mut parser = ZK4LWParser::new(ZK4LWParsePolicy::LENIENT);
+        let cons_34_resp =
+            ZK4LWConnections::build_response_with_parser(cons_34_resp_body.as_str(), &mut parser)
+                .unwrap();
+        assert_eq!(cons_34_resp.connections.len(), 1);
+        assert_eq!(cons_34_resp.connections[0].received, 0);
+        assert_eq!(cons_34_resp.connections[0].sent, 153);
+
+        let warnings = parser.into_warnings();
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings.iter().any(|w| w.field == "recved"));
+        assert!(warnings
+            .iter()
+            .any(|w| w.field.starts_with("/172.18.0.1:port")));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_serialize_and_deserialize_response() {
+        use crate::commands::cons::ZK4LWConnectionsResponse;
+
+        let cons_34_resp_body = fs::read_to_string("../../fixtures/3.4/cons.response").unwrap();
+        let cons_34_resp = ZK4LWConnections::build_response(cons_34_resp_body.as_str()).unwrap();
+
+        let json = serde_json::to_value(&cons_34_resp).unwrap();
+        assert_eq!(json["connections"][0]["address"], "172.18.0.7");
+        assert_eq!(json["connections"][0]["session_timeout"]["secs"], 30);
+        assert_eq!(
+            json["connections"][1]["session_id"],
+            serde_json::Value::Null
+        );
+
+        let cons_34_resp_de: ZK4LWConnectionsResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(cons_34_resp_de.connections, cons_34_resp.connections);
+    }
+
+    #[test]
+    fn should_build_response_from_zk35_connections_response_body() {
+        let cons_35_resp_body = fs::read_to_string("../../fixtures/3.5/cons.response").unwrap();
+        let cons_35_resp = ZK4LWConnections::build_response(cons_35_resp_body.as_str()).unwrap();
+
+        assert_eq!(cons_35_resp.connections.len(), 2);
+
+        let conn = &cons_35_resp.connections[1];
+        assert_eq!(conn.session_id, Some(0x1e00004b8d90001));
+        assert_eq!(conn.session_timeout, Some(Duration::from_secs(40)));
+        assert_eq!(conn.last_operation.as_deref(), Some("GETD"));
+        assert_eq!(conn.last_cxid, Some(0x3c));
+        assert_eq!(conn.last_zxid, Some(ZK4LWZxid::new(2, 2)));
+        assert_eq!(conn.last_latency, Some(Duration::from_millis(1)));
+        assert_eq!(conn.max_latency, Some(Duration::from_millis(9)));
+    }
+
+    #[test]
+    fn should_build_response_from_zk36_connections_response_body() {
+        let cons_36_resp_body = fs::read_to_string("../../fixtures/3.6/cons.response").unwrap();
+        let cons_36_resp = ZK4LWConnections::build_response(cons_36_resp_body.as_str()).unwrap();
+
+        assert_eq!(cons_36_resp.connections.len(), 3);
+        assert_eq!(
+            cons_36_resp
+                .connections
+                .iter()
+                .filter(|c| c.session_id.is_some())
+                .count(),
+            2
+        );
+
+        let conn = &cons_36_resp.connections[0];
+        assert_eq!(conn.avg_latency, Some(Duration::from_micros(275)));
+
+        let conn = &cons_36_resp.connections[2];
+        assert_eq!(conn.address, "::1".parse::<IpAddr>().unwrap());
+        assert_eq!(conn.session_timeout, Some(Duration::from_secs(4)));
+        assert_eq!(conn.last_operation.as_deref(), Some("CREA"));
+        assert_eq!(conn.avg_latency, Some(Duration::from_micros(800)));
+    }
+
+    #[test]
+    fn should_fail_to_build_response_from_unexpected_connections_response_body() {
+        assert!(ZK4LWConnections::build_response("")
+            .unwrap()
+            .connections
+            .is_empty());
+        assert!(ZK4LWConnections::build_response("not a connection").is_err());
+    }
+}
@@ -0,0 +1,113 @@
+//! The 4LW Directories command. Also known as "dirs".
+//!
+//! This command outputs the total size of the snapshot and transaction log files, in bytes.
+//!
+//! Available since: ZooKeeper 3.5.1
+
+use crate::{client::*, parsing::*, policy::*, result::*};
+
+const COMMAND: &str = "dirs";
+
+/// Fields always present in the response (i.e. enforced by `ZK4LWParsePolicy::STRICT`)
+const REQUIRED_FIELDS: &[&str] = &["datadir_size", "logdir_size"];
+
+/// Response to the `dirs` command
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWDirsResponse {
+    /// Size of the snapshot files in the data directory (in bytes)
+    pub datadir_size: i64,
+    /// Size of the transaction log files in the data log directory (in bytes)
+    pub logdir_size: i64,
+}
+
+impl ZK4LWDirsResponse {
+    /// Maps a field of the response, by key
+    fn map_field(&mut self, key: &str, val: &str) -> ZK4LWResult<()> {
+        match key {
+            "datadir_size" => self.datadir_size = val.parse()?,
+            "logdir_size" => self.logdir_size = val.parse()?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+/// The Directories (i.e. "dirs") command
+#[derive(Default)]
+pub struct ZK4LWDirs;
+
+impl ZK4LWCommand for ZK4LWDirs {
+    type Response = ZK4LWDirsResponse;
+
+    const NAME: &'static str = COMMAND;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        Self::build_response_with_parser(response_body, &mut ZK4LWParser::default())
+    }
+
+    fn build_response_with_parser(
+        response_body: &str,
+        parser: &mut ZK4LWParser,
+    ) -> ZK4LWResult<Self::Response> {
+        // Parse response body into key/value pairs
+        let response_map = colon_separated_bytes_to_key_value(response_body)?;
+        parser.require_fields(&response_map, REQUIRED_FIELDS)?;
+
+        // Map by key to a specific field in the response
+        let mut response = ZK4LWDirsResponse::default();
+        for (key, val) in response_map.into_iter() {
+            parser.field(key, response.map_field(key, val))?;
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::client::ZK4LWCommand;
+    use crate::commands::dirs::{ZK4LWDirs, ZK4LWDirsResponse};
+    use crate::errors::ZK4LWError;
+    use crate::policy::{ZK4LWParsePolicy, ZK4LWParser};
+
+    #[test]
+    fn should_build_response_from_zk35_dirs_response_body() {
+        let dirs_35_resp_body = fs::read_to_string("../../fixtures/3.5/dirs.response").unwrap();
+        let dirs_35_resp = ZK4LWDirs::build_response(dirs_35_resp_body.as_str()).unwrap();
+
+        assert_eq!(
+            dirs_35_resp,
+            ZK4LWDirsResponse {
+                datadir_size: 67108880,
+                logdir_size: 4096,
+            }
+        );
+    }
+
+    #[test]
+    fn should_build_response_from_zk36_dirs_response_body() {
+        let dirs_36_resp_body = fs::read_to_string("../../fixtures/3.6/dirs.response").unwrap();
+        let dirs_36_resp = ZK4LWDirs::build_response(dirs_36_resp_body.as_str()).unwrap();
+
+        assert_eq!(dirs_36_resp.datadir_size, 134218186);
+        assert_eq!(dirs_36_resp.logdir_size, 8192);
+    }
+
+    #[test]
+    fn should_fail_to_build_response_from_empty_dirs_response_body() {
+        // What a ZooKeeper 3.4 server, that doesn't know the command, responds
+        match ZK4LWDirs::build_response("") {
+            Err(ZK4LWError::MissingFieldError(field)) => assert_eq!(field, "datadir_size"),
+            _ => panic!("Expected MissingFieldError"),
+        }
+
+        let mut parser = ZK4LWParser::new(ZK4LWParsePolicy::LENIENT);
+        let dirs_resp = ZK4LWDirs::build_response_with_parser("", &mut parser).unwrap();
+        assert_eq!(dirs_resp, ZK4LWDirsResponse::default());
+        assert_eq!(parser.warnings().len(), 2);
+    }
+}
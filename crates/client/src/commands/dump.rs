@@ -0,0 +1,225 @@
+//! The 4LW Dump command. Also known as "dump".
+//!
+//! This command lists the outstanding sessions and ephemeral nodes.
+//!
+//! NOTE: Outstanding sessions are only tracked by the leader (or by a standalone server):
+//! when executed against any other server, this command fails with `ZK4LWError::NotLeaderError`,
+//! and should be retried against the leader. The response itself doesn't say what the state
+//! of the server is, so use `ZK4LWClient::execute_leader_only` to check it upfront.
+//!
+//! Available since: ZooKeeper 3.3.0
+
+use std::{collections::BTreeMap, time::SystemTime};
+
+use crate::{client::*, errors::*, parsing::*, result::*};
+
+const COMMAND: &str = "dump";
+
+const SESSIONS_SECTION_HEADER: &str = "SessionTracker dump:";
+const EPHEMERALS_SECTION_HEADER: &str = "ephemeral nodes dump:";
+const CONNECTIONS_SECTION_HEADER: &str = "Connections dump:";
+
+const SESSION_SETS_HEADER: &str = "Session Sets";
+const SESSIONS_WITH_EPHEMERALS_HEADER: &str = "Sessions with Ephemerals";
+const SESSION_SET_EXPIRATION_SEPARATOR: &str = "expire at";
+const GROUP_HEADER_SUFFIX: char = ':';
+
+/// Set of outstanding sessions, expiring at the same time
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWDumpSessionSet {
+    /// Expiration time, as reported by the server (i.e. in the format of Java's `Date::toString()`)
+    pub expire_at: String,
+    /// Expiration time: only available when the server reports it in the UTC (or GMT) time zone
+    pub expire_at_time: Option<SystemTime>,
+    /// Ids of the sessions in the set
+    pub session_ids: Vec<u64>,
+}
+
+/// Response to the `dump` command
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWDumpResponse {
+    /// Outstanding sessions, grouped in sets by expiration time
+    pub session_sets: Vec<ZK4LWDumpSessionSet>,
+    /// Paths of the ephemeral nodes, by session id
+    pub ephemerals: BTreeMap<u64, Vec<String>>,
+}
+
+impl ZK4LWDumpResponse {
+    /// Returns the ids of all the outstanding sessions
+    pub fn session_ids(&self) -> impl Iterator<Item = &u64> {
+        self.session_sets
+            .iter()
+            .flat_map(|set| set.session_ids.iter())
+    }
+}
+
+/// The Dump (i.e. "dump") command
+#[derive(Default)]
+pub struct ZK4LWDump;
+
+impl ZK4LWCommand for ZK4LWDump {
+    type Response = ZK4LWDumpResponse;
+
+    const NAME: &'static str = COMMAND;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        // Split the response body in its sections
+        let mut sessions_section = Vec::new();
+        let mut ephemerals_section = Vec::new();
+        let mut current_section = None;
+        for line in response_body.lines() {
+            match line.trim() {
+                SESSIONS_SECTION_HEADER => current_section = Some(&mut sessions_section),
+                EPHEMERALS_SECTION_HEADER => current_section = Some(&mut ephemerals_section),
+                CONNECTIONS_SECTION_HEADER => current_section = None,
+                _ => {
+                    if let Some(section) = current_section.as_mut() {
+                        section.push(line);
+                    }
+                }
+            }
+        }
+
+        // Followers and Observers only report the name of their session tracker (i.e. `LearnerSessionTracker`)
+        if !sessions_section
+            .iter()
+            .any(|l| l.contains(SESSION_SETS_HEADER))
+        {
+            return Err(ZK4LWError::NotLeaderError(COMMAND, None));
+        }
+
+        let mut response = ZK4LWDumpResponse::default();
+
+        // Parse "<N> expire at <date>:" groups
+        // NOTE: With local sessions enabled, both "Local Session Sets" and "Global Session Sets" are reported
+        let sessions_section: Vec<&str> = sessions_section
+            .into_iter()
+            .filter(|l| !l.contains(SESSION_SETS_HEADER))
+            .collect();
+        for (header, session_ids) in tab_indented_bytes_to_groups(&sessions_section.join("\n"))? {
+            let expire_at = header
+                .trim_end_matches(GROUP_HEADER_SUFFIX)
+                .split_once(SESSION_SET_EXPIRATION_SEPARATOR)
+                .ok_or_else(|| ZK4LWError::ParseStringError(header.to_string()))?
+                .1
+                .trim();
+            response.session_sets.push(ZK4LWDumpSessionSet {
+                expire_at: expire_at.into(),
+                expire_at_time: java_date_to_system_time(expire_at).ok(),
+                session_ids: session_ids
+                    .into_iter()
+                    .map(hex_to_u64)
+                    .collect::<ZK4LWResult<Vec<u64>>>()?,
+            });
+        }
+
+        // Parse "<session id>:" groups
+        let ephemerals_section: Vec<&str> = ephemerals_section
+            .into_iter()
+            .filter(|l| !l.starts_with(SESSIONS_WITH_EPHEMERALS_HEADER))
+            .collect();
+        for (header, paths) in tab_indented_bytes_to_groups(&ephemerals_section.join("\n"))? {
+            response.ephemerals.insert(
+                hex_to_u64(header.trim_end_matches(GROUP_HEADER_SUFFIX))?,
+                paths.into_iter().map(String::from).collect(),
+            );
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        time::{Duration, UNIX_EPOCH},
+    };
+
+    use crate::client::ZK4LWCommand;
+    use crate::commands::dump::{ZK4LWDump, ZK4LWDumpSessionSet};
+    use crate::errors::ZK4LWError;
+
+    #[test]
+    fn should_build_response_from_zk34_dump_response_body() {
+        let dump_34_resp_body = fs::read_to_string("../../fixtures/3.4/dump.response").unwrap();
+        let dump_34_resp = ZK4LWDump::build_response(dump_34_resp_body.as_str()).unwrap();
+
+        assert_eq!(dump_34_resp.session_sets.len(), 3);
+        assert_eq!(
+            dump_34_resp.session_sets[2],
+            ZK4LWDumpSessionSet {
+                expire_at: "Thu Aug 06 16:29:48 UTC 2020".into(),
+                expire_at_time: Some(UNIX_EPOCH + Duration::from_secs(1596731388)),
+                session_ids: vec![0x1e0000c3e670000],
+            }
+        );
+        assert_eq!(
+            dump_34_resp.session_ids().collect::<Vec<&u64>>(),
+            vec![&0x1e0000c3e670000]
+        );
+        assert_eq!(dump_34_resp.ephemerals.len(), 1);
+        assert_eq!(
+            dump_34_resp.ephemerals.get(&0x1e0000c3e670000).unwrap(),
+            &vec!["/brokers/ids/0".to_string()]
+        );
+    }
+
+    #[test]
+    fn should_build_response_from_zk35_dump_response_body() {
+        let dump_35_resp_body = fs::read_to_string("../../fixtures/3.5/dump.response").unwrap();
+        let dump_35_resp = ZK4LWDump::build_response(dump_35_resp_body.as_str()).unwrap();
+
+        assert_eq!(dump_35_resp.session_sets.len(), 3);
+        assert_eq!(dump_35_resp.session_ids().count(), 2);
+        assert_eq!(
+            dump_35_resp.session_sets[1].session_ids,
+            vec![0x1e00004b8d90002]
+        );
+        assert_eq!(dump_35_resp.ephemerals.len(), 2);
+        assert_eq!(
+            dump_35_resp.ephemerals.get(&0x1e00004b8d90002).unwrap(),
+            &vec!["/controller".to_string(), "/brokers/ids/2".to_string()]
+        );
+    }
+
+    #[test]
+    fn should_build_response_from_zk36_dump_response_body() {
+        let dump_36_resp_body = fs::read_to_string("../../fixtures/3.6/dump.response").unwrap();
+        let dump_36_resp = ZK4LWDump::build_response(dump_36_resp_body.as_str()).unwrap();
+
+        assert_eq!(dump_36_resp.session_sets.len(), 2);
+        assert_eq!(
+            dump_36_resp.session_ids().copied().collect::<Vec<u64>>(),
+            vec![0x1e0000a1f2c0000, 0x1e0000a1f2c0001]
+        );
+        assert_eq!(dump_36_resp.ephemerals.len(), 1);
+        assert_eq!(
+            dump_36_resp
+                .ephemerals
+                .get(&0x1e0000a1f2c0001)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn should_fail_to_build_response_from_follower_dump_response_body() {
+        let dump_resp = ZK4LWDump::build_response(
+            "SessionTracker dump:\n\
+             org.apache.zookeeper.server.quorum.LearnerSessionTracker@6d4e5011\n\
+             ephemeral nodes dump:\n\
+             Sessions with Ephemerals (1):\n\
+             0x1e0000a1f2c0001:\n\
+             \t/brokers/ids/1\n",
+        );
+
+        match dump_resp {
+            Err(ZK4LWError::NotLeaderError(command, None)) => assert_eq!(command, "dump"),
+            _ => panic!("Expected NotLeaderError"),
+        }
+    }
+}
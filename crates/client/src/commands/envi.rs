@@ -0,0 +1,255 @@
+//! The 4LW Environment command. Also known as "envi".
+//!
+//! This command outputs details about the serving environment,
+//! like the ZooKeeper version, the JVM and the Operating System.
+//!
+//! Available since: ZooKeeper 3.3.0
+
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::{client::*, parsing::*, policy::*, result::*, version::*};
+
+const COMMAND: &str = "envi";
+
+/// Fields always present in the response (i.e. enforced by `ZK4LWParsePolicy::STRICT`)
+const REQUIRED_FIELDS: &[&str] = &[
+    "zookeeper.version",
+    "host.name",
+    "java.version",
+    "java.vendor",
+    "java.home",
+    "java.class.path",
+    "java.library.path",
+    "java.io.tmpdir",
+    "java.compiler",
+    "os.name",
+    "os.arch",
+    "os.version",
+    "user.name",
+    "user.home",
+    "user.dir",
+];
+
+/// Response to the `envi` command
+///
+/// The fields are "divided" into 3 "classes":
+///
+/// * mapped & always present: they will always be in the response,
+///   and the value will always be valid
+/// * mapped & sometimes present: they will always be in the response,
+///   but their value is an `Option` that will be set to `None` if missing -
+///   a value can be missing because the value is not reported by the
+///   specific instance of ZooKeeper against which the command was executed
+/// * unmapped: when a value hasn't been mapped (yet), its stored in the
+///   `misc` field, that is an hash-map - the idea is that we then release
+///   an update that maps it
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWEnvironmentResponse {
+    // version
+    pub version: ZK4LWVersion,
+    // host
+    pub host_name: String,
+    // java
+    pub java_version: String,
+    pub java_vendor: String,
+    pub java_home: PathBuf,
+    pub java_class_path: Vec<PathBuf>,
+    pub java_library_path: Vec<PathBuf>,
+    pub java_io_tmpdir: PathBuf,
+    pub java_compiler: String,
+    // os
+    pub os_name: String,
+    pub os_arch: String,
+    pub os_version: String,
+    // user
+    pub user_name: String,
+    pub user_home: PathBuf,
+    pub user_dir: PathBuf,
+    // memory (in bytes)
+    /// NOTE: memory details are only reported by ZK >= 3.5.x
+    pub os_memory_free: Option<i64>,
+    pub os_memory_max: Option<i64>,
+    pub os_memory_total: Option<i64>,
+    // unknown/unmapped fields
+    pub misc: HashMap<String, String>,
+}
+
+impl ZK4LWEnvironmentResponse {
+    /// Maps a field of the response, by key
+    fn map_field(&mut self, key: &str, val: &str) -> ZK4LWResult<()> {
+        match key {
+            "zookeeper.version" => self.version = val.parse()?,
+            // host
+            "host.name" => self.host_name = val.into(),
+            // java
+            "java.version" => self.java_version = val.into(),
+            "java.vendor" => self.java_vendor = val.into(),
+            "java.home" => self.java_home = val.into(),
+            "java.class.path" => self.java_class_path = path_list_to_paths(val),
+            "java.library.path" => self.java_library_path = path_list_to_paths(val),
+            "java.io.tmpdir" => self.java_io_tmpdir = val.into(),
+            "java.compiler" => self.java_compiler = val.into(),
+            // os
+            "os.name" => self.os_name = val.into(),
+            "os.arch" => self.os_arch = val.into(),
+            "os.version" => self.os_version = val.into(),
+            // user
+            "user.name" => self.user_name = val.into(),
+            "user.home" => self.user_home = val.into(),
+            "user.dir" => self.user_dir = val.into(),
+            // memory
+            "os.memory.free" => self.os_memory_free = Some(memory_size_to_bytes(val)?),
+            "os.memory.max" => self.os_memory_max = Some(memory_size_to_bytes(val)?),
+            "os.memory.total" => self.os_memory_total = Some(memory_size_to_bytes(val)?),
+            _ => {
+                self.misc.insert(key.into(), val.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The Environment (i.e. "envi") command
+#[derive(Default)]
+pub struct ZK4LWEnvironment;
+
+impl ZK4LWCommand for ZK4LWEnvironment {
+    type Response = ZK4LWEnvironmentResponse;
+
+    const NAME: &'static str = COMMAND;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        Self::build_response_with_parser(response_body, &mut ZK4LWParser::default())
+    }
+
+    fn build_response_with_parser(
+        response_body: &str,
+        parser: &mut ZK4LWParser,
+    ) -> ZK4LWResult<Self::Response> {
+        // Parse response body into key/value pairs
+        let response_map = equal_separated_bytes_to_key_value(response_body)?;
+        parser.require_fields(&response_map, REQUIRED_FIELDS)?;
+
+        // Map by key to a specific field in the response
+        let mut response = ZK4LWEnvironmentResponse::default();
+        for (key, val) in response_map.into_iter() {
+            parser.field(key, response.map_field(key, val))?;
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::client::ZK4LWCommand;
+    use crate::commands::envi::ZK4LWEnvironment;
+    use crate::version::ZK4LWVersion;
+
+    const MB: i64 = 1024 * 1024;
+
+    #[test]
+    fn should_build_response_from_zk34_environment_response_body() {
+        let envi_34_resp_body = fs::read_to_string("../../fixtures/3.4/envi.response").unwrap();
+        let envi_34_resp = ZK4LWEnvironment::build_response(envi_34_resp_body.as_str()).unwrap();
+
+        assert_eq!(envi_34_resp.version, ZK4LWVersion::new(3, 4, 14));
+        assert_eq!(
+            envi_34_resp.version.revision.as_deref(),
+            Some("4c25d480e66aadd371de8bd2fd8da255ac140bcf")
+        );
+        assert_eq!(
+            envi_34_resp.version.build_date.as_deref(),
+            Some("03/06/2019 16:18 GMT")
+        );
+        assert_eq!(envi_34_resp.host_name, "zk30");
+        assert_eq!(envi_34_resp.java_version, "1.8.0_265");
+        assert_eq!(envi_34_resp.java_vendor, "Oracle Corporation");
+        assert_eq!(
+            envi_34_resp.java_home,
+            PathBuf::from("/usr/local/openjdk-8")
+        );
+        assert_eq!(envi_34_resp.java_class_path.len(), 13);
+        assert_eq!(
+            envi_34_resp.java_class_path.last().unwrap(),
+            &PathBuf::from("/conf")
+        );
+        assert_eq!(envi_34_resp.java_library_path.len(), 5);
+        assert_eq!(envi_34_resp.java_io_tmpdir, PathBuf::from("/tmp"));
+        assert_eq!(envi_34_resp.java_compiler, "<NA>");
+        assert_eq!(envi_34_resp.os_name, "Linux");
+        assert_eq!(envi_34_resp.os_arch, "amd64");
+        assert_eq!(envi_34_resp.os_version, "4.19.76-linuxkit");
+        assert_eq!(envi_34_resp.user_name, "zookeeper");
+        assert_eq!(envi_34_resp.user_home, PathBuf::from("/home/zookeeper"));
+        assert_eq!(envi_34_resp.user_dir, PathBuf::from("/zookeeper-3.4.14"));
+        assert_eq!(envi_34_resp.os_memory_free, None);
+        assert_eq!(envi_34_resp.os_memory_max, None);
+        assert_eq!(envi_34_resp.os_memory_total, None);
+        assert_eq!(envi_34_resp.misc.len(), 0);
+    }
+
+    #[test]
+    fn should_build_response_from_zk35_environment_response_body() {
+        let envi_35_resp_body = fs::read_to_string("../../fixtures/3.5/envi.response").unwrap();
+        let envi_35_resp = ZK4LWEnvironment::build_response(envi_35_resp_body.as_str()).unwrap();
+
+        assert_eq!(envi_35_resp.version, ZK4LWVersion::new(3, 5, 8));
+        assert_eq!(
+            envi_35_resp.version.revision.as_deref(),
+            Some("f439ca583e70862c3068a1f2a7d4d068eec33315")
+        );
+        assert_eq!(
+            envi_35_resp.version.build_date.as_deref(),
+            Some("05/04/2020 15:07 GMT")
+        );
+        assert_eq!(envi_35_resp.java_version, "11.0.8");
+        assert_eq!(envi_35_resp.java_vendor, "N/A");
+        assert_eq!(
+            envi_35_resp.java_home,
+            PathBuf::from("/usr/local/openjdk-11")
+        );
+        assert_eq!(envi_35_resp.java_class_path.len(), 34);
+        assert_eq!(
+            envi_35_resp.java_class_path.first().unwrap(),
+            &PathBuf::from("/apache-zookeeper-3.5.8-bin/bin/../zookeeper-server/target/classes")
+        );
+        assert_eq!(envi_35_resp.os_name, "Linux");
+        assert_eq!(envi_35_resp.os_arch, "amd64");
+        assert_eq!(envi_35_resp.os_memory_free, Some(36 * MB));
+        assert_eq!(envi_35_resp.os_memory_max, Some(1000 * MB));
+        assert_eq!(envi_35_resp.os_memory_total, Some(48 * MB));
+        assert_eq!(envi_35_resp.misc.len(), 0);
+    }
+
+    #[test]
+    fn should_build_response_from_zk36_environment_response_body() {
+        let envi_36_resp_body = fs::read_to_string("../../fixtures/3.6/envi.response").unwrap();
+        let envi_36_resp = ZK4LWEnvironment::build_response(envi_36_resp_body.as_str()).unwrap();
+
+        assert_eq!(envi_36_resp.version, ZK4LWVersion::new(3, 6, 1));
+        assert_eq!(
+            envi_36_resp.version.revision.as_deref(),
+            Some("104dcb3e3fb464b30c5186d229e00af9f332524b")
+        );
+        assert_eq!(
+            envi_36_resp.version.build_date.as_deref(),
+            Some("04/21/2020 15:01 GMT")
+        );
+        assert_eq!(envi_36_resp.java_version, "11.0.8");
+        assert_eq!(envi_36_resp.java_class_path.len(), 42);
+        assert_eq!(envi_36_resp.os_version, "4.19.76-linuxkit");
+        assert_eq!(
+            envi_36_resp.user_dir,
+            PathBuf::from("/apache-zookeeper-3.6.1-bin")
+        );
+        assert_eq!(envi_36_resp.os_memory_free, Some(30 * MB));
+        assert_eq!(envi_36_resp.os_memory_max, Some(1000 * MB));
+        assert_eq!(envi_36_resp.os_memory_total, Some(48 * MB));
+        assert_eq!(envi_36_resp.misc.len(), 0);
+    }
+}
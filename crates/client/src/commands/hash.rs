@@ -0,0 +1,126 @@
+//! The 4LW Hash command. Also known as "hash".
+//!
+//! This command outputs the history of the digests of the data tree, by Zxid:
+//! it's useful to detect data inconsistencies between the members of the ensemble.
+//!
+//! NOTE: The server only records digests if `zookeeper.digest.enabled` is set.
+//!
+//! Available since: ZooKeeper 3.6.0
+
+use crate::{client::*, errors::*, policy::*, result::*, zxid::*};
+
+const COMMAND: &str = "hash";
+const DIGEST_HISTORIES_HEADER: &str = "digest histories:";
+const ZXID_DIGEST_SEPARATOR: char = ':';
+
+/// Digest of the data tree, at a given Zxid
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWZxidDigest {
+    pub zxid: ZK4LWZxid,
+    pub digest: i64,
+}
+
+/// Response to the `hash` command
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWHashResponse {
+    /// Digests, in the order reported by the server (i.e. from the oldest Zxid)
+    pub digests: Vec<ZK4LWZxidDigest>,
+}
+
+/// The Hash (i.e. "hash") command
+#[derive(Default)]
+pub struct ZK4LWHash;
+
+impl ZK4LWCommand for ZK4LWHash {
+    type Response = ZK4LWHashResponse;
+
+    const NAME: &'static str = COMMAND;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        Self::build_response_with_parser(response_body, &mut ZK4LWParser::default())
+    }
+
+    fn build_response_with_parser(
+        response_body: &str,
+        parser: &mut ZK4LWParser,
+    ) -> ZK4LWResult<Self::Response> {
+        let mut response = ZK4LWHashResponse::default();
+
+        // Digests that fail to parse are skipped, if the parser is lenient
+        for line in response_body
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.eq_ignore_ascii_case(DIGEST_HISTORIES_HEADER))
+        {
+            match line_to_zxid_digest(line) {
+                Ok(digest) => response.digests.push(digest),
+                Err(error) => parser.field(line, Err(error))?,
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+/// Parses a "<hex zxid>: <digest>" line
+fn line_to_zxid_digest(line: &str) -> ZK4LWResult<ZK4LWZxidDigest> {
+    let (zxid, digest) = line
+        .split_once(ZXID_DIGEST_SEPARATOR)
+        .ok_or_else(|| ZK4LWError::ParseStringError(line.to_string()))?;
+
+    Ok(ZK4LWZxidDigest {
+        zxid: zxid.parse()?,
+        digest: digest.trim().parse()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::client::ZK4LWCommand;
+    use crate::commands::hash::{ZK4LWHash, ZK4LWZxidDigest};
+    use crate::policy::{ZK4LWParsePolicy, ZK4LWParser};
+    use crate::zxid::ZK4LWZxid;
+
+    #[test]
+    fn should_build_response_from_zk36_hash_response_body() {
+        let hash_36_resp_body = fs::read_to_string("../../fixtures/3.6/hash.response").unwrap();
+        let hash_36_resp = ZK4LWHash::build_response(hash_36_resp_body.as_str()).unwrap();
+
+        assert_eq!(hash_36_resp.digests.len(), 4);
+        assert_eq!(
+            hash_36_resp.digests[0],
+            ZK4LWZxidDigest {
+                zxid: ZK4LWZxid::new(3, 1),
+                digest: 1496318785,
+            }
+        );
+        assert_eq!(hash_36_resp.digests[3].zxid, ZK4LWZxid::new(3, 4));
+    }
+
+    #[test]
+    fn should_fail_to_build_response_from_unexpected_hash_response_body() {
+        assert!(ZK4LWHash::build_response("digest histories:\n")
+            .unwrap()
+            .digests
+            .is_empty());
+        assert!(ZK4LWHash::build_response("digest histories:\n300000001\n").is_err());
+    }
+
+    #[test]
+    fn should_keep_going_on_bad_digests_if_lenient() {
+        let hash_resp_body = "digest histories:\n300000001\n300000002: 4096745651\n";
+
+        let mut parser = ZK4LWParser::new(ZK4LWParsePolicy::LENIENT);
+        let hash_resp = ZK4LWHash::build_response_with_parser(hash_resp_body, &mut parser).unwrap();
+        assert_eq!(hash_resp.digests.len(), 1);
+        assert_eq!(hash_resp.digests[0].zxid, ZK4LWZxid::new(3, 2));
+
+        let warnings = parser.into_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "300000001");
+    }
+}
@@ -0,0 +1,68 @@
+//! The 4LW "Is read-only?" command. Also known as "isro".
+//!
+//! This command tests if the server is running in read-only mode:
+//! a server switches to read-only mode when it's partitioned from the quorum,
+//! and `readonlymode.enabled` is set.
+//!
+//! Available since: ZooKeeper 3.4.0
+
+use crate::{client::*, errors::*, result::*};
+
+const COMMAND: &str = "isro";
+const RESPONSE_READONLY: &str = "ro";
+const RESPONSE_READWRITE: &str = "rw";
+
+/// Response to the `isro` command
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum ZK4LWIsReadOnlyResponse {
+    /// The server is in read-only mode
+    READONLY,
+    /// The server is not in read-only mode
+    READWRITE,
+}
+
+/// The "Is read-only?" (i.e. "isro") command
+#[derive(Default)]
+pub struct ZK4LWIsReadOnly;
+
+impl ZK4LWCommand for ZK4LWIsReadOnly {
+    type Response = ZK4LWIsReadOnlyResponse;
+
+    const NAME: &'static str = COMMAND;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        match response_body.trim() {
+            RESPONSE_READONLY => Ok(ZK4LWIsReadOnlyResponse::READONLY),
+            RESPONSE_READWRITE => Ok(ZK4LWIsReadOnlyResponse::READWRITE),
+            unexpected => Err(ZK4LWError::UnexpectedResponseError(unexpected.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::client::ZK4LWCommand;
+    use crate::commands::isro::{ZK4LWIsReadOnly, ZK4LWIsReadOnlyResponse};
+
+    #[test]
+    fn should_build_response_from_isro_response_bodies() {
+        for version in ["3.4", "3.5", "3.6"].iter() {
+            let isro_resp_body =
+                fs::read_to_string(format!("../../fixtures/{}/isro.response", version)).unwrap();
+            assert_eq!(
+                ZK4LWIsReadOnly::build_response(isro_resp_body.as_str()).unwrap(),
+                ZK4LWIsReadOnlyResponse::READWRITE
+            );
+        }
+
+        assert_eq!(
+            ZK4LWIsReadOnly::build_response("ro").unwrap(),
+            ZK4LWIsReadOnlyResponse::READONLY
+        );
+        assert!(ZK4LWIsReadOnly::build_response("null").is_err());
+    }
+}
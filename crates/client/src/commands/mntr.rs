@@ -7,9 +7,219 @@
 
 use std::collections::HashMap;
 
-use crate::{client::*, commands::common::*, errors::*, parsing::*, result::*, state::*};
+use crate::{
+    client::*, commands::common::*, parsing::*, policy::*, result::*, state::*, version::*,
+};
 
-const COMMAND: &'static str = "mntr";
+const COMMAND: &str = "mntr";
+
+/// Fields always present in the response (i.e. enforced by `ZK4LWParsePolicy::STRICT`)
+///
+/// NOTE: file descriptors counts are not required, as they are only reported on Unix systems.
+const REQUIRED_FIELDS: &[&str] = &[
+    "zk_version",
+    "zk_avg_latency",
+    "zk_max_latency",
+    "zk_min_latency",
+    "zk_packets_received",
+    "zk_packets_sent",
+    "zk_num_alive_connections",
+    "zk_outstanding_requests",
+    "zk_server_state",
+    "zk_znode_count",
+    "zk_watch_count",
+    "zk_ephemerals_count",
+    "zk_approximate_data_size",
+];
+
+const LATENCY: &str = "latency";
+const LEARNER_HANDLER_QP_SIZE_SUFFIX: &str = "_learner_handler_qp_size";
+const LEARNER_HANDLER_QP_TIME_SUFFIX: &str = "_learner_handler_qp_time_ms";
+const WRITE_PER_NAMESPACE_SUFFIX: &str = "_write_per_namespace";
+
+/// Quorum metrics: election, synchronization of learners, proposals and commits
+///
+/// NOTE: Only reported by ZK >= 3.6.x, and mostly only by the "Leader".
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWMonitorQuorum {
+    pub quorum_size: Option<i64>,
+    pub looking_count: Option<i64>,
+    /// Time since this server became the "Leader" (in ms)
+    pub leader_uptime: Option<i64>,
+    pub election_time: Option<ZK4LWMetricSample>,
+    pub quit_leading_due_to_disloyal_voter: Option<i64>,
+    // learners synchronization
+    pub diff_count: Option<i64>,
+    pub snap_count: Option<i64>,
+    pub follower_sync_time: Option<ZK4LWMetricSample>,
+    // proposals and commits
+    pub proposal_count: Option<i64>,
+    pub commit_count: Option<i64>,
+    pub learner_proposal_received_count: Option<i64>,
+    pub learner_commit_received_count: Option<i64>,
+    pub proposal_latency: Option<ZK4LWTimeMetricSample>,
+    pub proposal_ack_creation_latency: Option<ZK4LWTimeMetricSample>,
+    pub quorum_ack_latency: Option<ZK4LWTimeMetricSample>,
+    pub commit_propagation_latency: Option<ZK4LWTimeMetricSample>,
+    pub propagation_latency: Option<ZK4LWTimeMetricSample>,
+    // observer master
+    pub om_proposal_process_time_ms: Option<ZK4LWTimeMetricSample>,
+    pub om_commit_process_time_ms: Option<ZK4LWTimeMetricSample>,
+    /// Size of the queue of packets to send to each learner, by server id
+    pub learner_handler_qp_size: HashMap<i64, ZK4LWMetricSample>,
+    /// Time packets spend in the queue to each learner (in ms), by server id
+    pub learner_handler_qp_time_ms: HashMap<i64, ZK4LWTimeMetricSample>,
+}
+
+/// Prep Request Processor metrics (i.e. the first stage of the request pipeline)
+///
+/// NOTE: Only reported by ZK >= 3.6.x
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWMonitorPrepProcessor {
+    pub prep_processor_request_queued: Option<i64>,
+    pub prep_processor_queue_size: Option<ZK4LWMetricSample>,
+    pub prep_processor_queue_time_ms: Option<ZK4LWTimeMetricSample>,
+    pub prep_process_time: Option<ZK4LWMetricSample>,
+    pub close_session_prep_time: Option<ZK4LWTimeMetricSample>,
+    pub outstanding_changes_queued: Option<i64>,
+    pub outstanding_changes_removed: Option<i64>,
+}
+
+/// Commit Processor metrics
+///
+/// NOTE: Only reported by ZK >= 3.6.x
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWMonitorCommitProcessor {
+    pub request_commit_queued: Option<i64>,
+    // queues
+    pub read_commit_proc_req_queued: Option<ZK4LWMetricSample>,
+    pub write_commit_proc_req_queued: Option<ZK4LWMetricSample>,
+    pub commit_commit_proc_req_queued: Option<ZK4LWMetricSample>,
+    pub read_commit_proc_issued: Option<ZK4LWMetricSample>,
+    pub write_commit_proc_issued: Option<ZK4LWMetricSample>,
+    // session queues
+    pub session_queues_drained: Option<ZK4LWMetricSample>,
+    pub pending_session_queue_size: Option<ZK4LWMetricSample>,
+    pub requests_in_session_queue: Option<ZK4LWMetricSample>,
+    pub reads_issued_from_session_queue: Option<ZK4LWMetricSample>,
+    pub reads_after_write_in_session_queue: Option<ZK4LWMetricSample>,
+    // processing
+    pub commit_process_time: Option<ZK4LWMetricSample>,
+    pub concurrent_request_processing_in_commit_processor: Option<ZK4LWMetricSample>,
+    pub time_waiting_empty_pool_in_commit_processor_read_ms: Option<ZK4LWMetricSample>,
+    pub write_batch_time_in_commit_processor: Option<ZK4LWMetricSample>,
+    pub read_commitproc_time_ms: Option<ZK4LWTimeMetricSample>,
+    pub write_commitproc_time_ms: Option<ZK4LWTimeMetricSample>,
+    pub local_write_committed_time_ms: Option<ZK4LWTimeMetricSample>,
+    pub server_write_committed_time_ms: Option<ZK4LWTimeMetricSample>,
+}
+
+/// Sync Request Processor metrics (i.e. persistence of transactions and snapshots)
+///
+/// NOTE: Only reported by ZK >= 3.6.x
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWMonitorSyncProcessor {
+    pub sync_processor_request_queued: Option<i64>,
+    pub sync_processor_queue_size: Option<ZK4LWMetricSample>,
+    pub sync_processor_batch_size: Option<ZK4LWMetricSample>,
+    pub sync_processor_queue_time_ms: Option<ZK4LWTimeMetricSample>,
+    pub sync_processor_queue_flush_time_ms: Option<ZK4LWTimeMetricSample>,
+    pub sync_processor_queue_and_flush_time_ms: Option<ZK4LWTimeMetricSample>,
+    pub sync_process_time: Option<ZK4LWMetricSample>,
+    pub fsynctime: Option<ZK4LWMetricSample>,
+    pub snapshottime: Option<ZK4LWMetricSample>,
+}
+
+/// Requests metrics: completion latency, staleness and throttling
+///
+/// NOTE: Only reported by ZK >= 3.6.x
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWMonitorRequests {
+    pub readlatency: Option<ZK4LWTimeMetricSample>,
+    pub updatelatency: Option<ZK4LWTimeMetricSample>,
+    pub read_final_proc_time_ms: Option<ZK4LWTimeMetricSample>,
+    pub write_final_proc_time_ms: Option<ZK4LWTimeMetricSample>,
+    pub stale_requests: Option<i64>,
+    pub stale_requests_dropped: Option<i64>,
+    pub stale_replies: Option<i64>,
+    pub large_requests_rejected: Option<i64>,
+    pub request_throttle_wait_count: Option<i64>,
+}
+
+/// Session metrics
+///
+/// NOTE: Only reported by ZK >= 3.6.x
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWMonitorSessions {
+    pub global_sessions: Option<i64>,
+    pub local_sessions: Option<i64>,
+    pub stale_sessions_expired: Option<i64>,
+    pub revalidate_count: Option<i64>,
+}
+
+/// TLS metrics
+///
+/// NOTE: Only reported by ZK >= 3.6.x
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWMonitorTls {
+    pub outstanding_handshake: Option<i64>,
+    pub handshake_exceeded: Option<i64>,
+}
+
+/// Ensemble (i.e. Quorum SASL) authentication metrics
+///
+/// NOTE: Only reported by ZK >= 3.6.x
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWMonitorEnsembleAuth {
+    pub success: Option<i64>,
+    pub fail: Option<i64>,
+    pub skip: Option<i64>,
+}
+
+/// Response packets caches metrics
+///
+/// NOTE: Only reported by ZK >= 3.6.x
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWMonitorCaches {
+    pub response_packet_cache_hits: Option<i64>,
+    pub response_packet_cache_misses: Option<i64>,
+    pub response_packet_get_children_cache_hits: Option<i64>,
+    pub response_packet_get_children_cache_misses: Option<i64>,
+}
+
+/// Watch events metrics: number of watches triggered by each type of event
+///
+/// NOTE: Only reported by ZK >= 3.6.x
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWMonitorWatchEvents {
+    pub node_created_watch_count: Option<ZK4LWMetricSample>,
+    pub node_deleted_watch_count: Option<ZK4LWMetricSample>,
+    pub node_changed_watch_count: Option<ZK4LWMetricSample>,
+    pub node_children_watch_count: Option<ZK4LWMetricSample>,
+    pub dead_watchers_cleaner_latency: Option<ZK4LWTimeMetricSample>,
+}
+
+/// Startup metrics: loading of the database from snapshots and transaction logs
+///
+/// NOTE: Only reported by ZK >= 3.6.x
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWMonitorStartup {
+    pub dbinittime: Option<ZK4LWMetricSample>,
+    pub startup_snap_load_time: Option<ZK4LWMetricSample>,
+    pub startup_txns_load_time: Option<ZK4LWMetricSample>,
+    pub startup_txns_loaded: Option<ZK4LWMetricSample>,
+}
 
 /// Response to the `mntr` command
 ///
@@ -25,17 +235,26 @@ const COMMAND: &'static str = "mntr";
 /// * unmapped: when a value hasn't been mapped (yet), its stored in the
 ///   `misc` field, that is an hash-map - the idea is that we then release
 ///   an update that maps it
+///
+/// The (many) metrics introduced by ZK 3.6.x are grouped by area in sub-structures
+/// (e.g. `quorum`, `commit_processor`): "summary" metrics, reported as
+/// `zk_<stat>_<name>` (e.g. `zk_avg_fsynctime`), are collected in a single
+/// `ZK4LWMetricSample` or, if they report percentiles, `ZK4LWTimeMetricSample`.
+/// Unmapped summary metrics (e.g. introduced by a newer ZooKeeper release) are
+/// stored, already reassembled, in the `misc_summaries` field.
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ZK4LWMonitorResponse {
     // version
-    pub version: String,
-    pub build_revision: String,
-    pub build_date: String,
+    pub version: ZK4LWVersion,
+    // uptime (in ms)
+    pub uptime: Option<i64>,
     // latency
     pub latency: ZK4LWMetricSample,
     // packets
     pub packets_received: i64,
     pub packets_sent: i64,
+    pub bytes_received_count: Option<i64>,
     // connections
     pub num_alive_connections: i64,
     pub connection_drop_count: Option<i64>,
@@ -43,7 +262,9 @@ pub struct ZK4LWMonitorResponse {
     pub connection_rejected: Option<i64>,
     pub connection_request_count: Option<i64>,
     pub connection_revalidate_count: Option<i64>,
+    pub connection_token_deficit: Option<ZK4LWMetricSample>,
     pub sessionless_connections_expired: Option<i64>,
+    pub netty_queued_buffer_capacity: Option<ZK4LWMetricSample>,
     // watchers
     pub add_dead_watcher_stall_time: Option<i64>,
     pub dead_watchers_cleared: Option<i64>,
@@ -52,12 +273,16 @@ pub struct ZK4LWMonitorResponse {
     pub outstanding_requests: i64,
     // state
     pub server_state: ZK4LWServerState,
+    /// State of the peer in the quorum, with the phase it's in (e.g. "leading - broadcast")
+    pub peer_state: Option<String>,
     // znodes
     pub znode_count: i64,
     pub watch_count: i64,
     pub ephemerals_count: i64,
     // data size
     pub approximate_data_size: i64,
+    /// Size of the write requests, by namespace (i.e. top level znode)
+    pub write_per_namespace: HashMap<String, ZK4LWMetricSample>,
     // file descriptors
     pub open_file_descriptor_count: i64,
     pub max_file_descriptor_count: i64,
@@ -73,116 +298,394 @@ pub struct ZK4LWMonitorResponse {
     pub last_proposal_size: Option<i64>,
     pub max_proposal_size: Option<i64>,
     pub min_proposal_size: Option<i64>,
+    // client responses
+    pub last_client_response_size: Option<i64>,
+    pub max_client_response_size: Option<i64>,
+    pub min_client_response_size: Option<i64>,
+    // errors
+    pub digest_mismatches_count: Option<i64>,
+    pub unrecoverable_error_count: Option<i64>,
+    // grouped metrics
+    pub quorum: ZK4LWMonitorQuorum,
+    pub prep_processor: ZK4LWMonitorPrepProcessor,
+    pub commit_processor: ZK4LWMonitorCommitProcessor,
+    pub sync_processor: ZK4LWMonitorSyncProcessor,
+    pub requests: ZK4LWMonitorRequests,
+    pub sessions: ZK4LWMonitorSessions,
+    pub tls: ZK4LWMonitorTls,
+    pub ensemble_auth: ZK4LWMonitorEnsembleAuth,
+    pub caches: ZK4LWMonitorCaches,
+    pub watch_events: ZK4LWMonitorWatchEvents,
+    pub startup: ZK4LWMonitorStartup,
     // unknown/unmapped fields
     pub misc: HashMap<String, String>,
+    pub misc_summaries: ZK4LWSummaryMetrics,
+}
+
+impl ZK4LWMonitorResponse {
+    /// Maps a field of the response, by key
+    fn map_field(&mut self, key: &str, val: &str) -> ZK4LWResult<()> {
+        match key {
+            "zk_version" => self.version = val.parse()?,
+            // uptime
+            "zk_uptime" => self.uptime = Some(val.parse()?),
+            // packets
+            "zk_packets_received" => self.packets_received = val.parse()?,
+            "zk_packets_sent" => self.packets_sent = val.parse()?,
+            "zk_bytes_received_count" => self.bytes_received_count = Some(val.parse()?),
+            // connections
+            "zk_num_alive_connections" => self.num_alive_connections = val.parse()?,
+            "zk_connection_drop_count" => self.connection_drop_count = Some(val.parse()?),
+            "zk_connection_drop_probability" => {
+                self.connection_drop_probability = Some(val.parse()?)
+            }
+            "zk_connection_rejected" => self.connection_rejected = Some(val.parse()?),
+            "zk_connection_request_count" => self.connection_request_count = Some(val.parse()?),
+            "zk_connection_revalidate_count" => {
+                self.connection_revalidate_count = Some(val.parse()?)
+            }
+            "zk_sessionless_connections_expired" => {
+                self.sessionless_connections_expired = Some(val.parse()?)
+            }
+            // watchers
+            "zk_add_dead_watcher_stall_time" => {
+                self.add_dead_watcher_stall_time = Some(val.parse()?)
+            }
+            "zk_dead_watchers_cleared" => self.dead_watchers_cleared = Some(val.parse()?),
+            "zk_dead_watchers_queued" => self.dead_watchers_queued = Some(val.parse()?),
+            // requests
+            "zk_outstanding_requests" => self.outstanding_requests = val.parse()?,
+            // state
+            "zk_server_state" => self.server_state = val.parse()?,
+            "zk_peer_state" => self.peer_state = Some(val.into()),
+            // znodes
+            "zk_znode_count" => self.znode_count = val.parse()?,
+            "zk_watch_count" => self.watch_count = val.parse()?,
+            "zk_ephemerals_count" => self.ephemerals_count = val.parse()?,
+            // data size
+            "zk_approximate_data_size" => self.approximate_data_size = val.parse()?,
+            // file descriptors
+            "zk_open_file_descriptor_count" => self.open_file_descriptor_count = val.parse()?,
+            "zk_max_file_descriptor_count" => self.max_file_descriptor_count = val.parse()?,
+            // followers
+            "zk_followers" => self.learners = Some(val.parse()?), //< NOTE: synonym of "zk_learners" in ZK < 3.6.x
+            "zk_learners" => self.learners = Some(val.parse()?),
+            "zk_synced_followers" => self.synced_followers = Some(val.parse()?),
+            "zk_pending_syncs" => self.pending_syncs = Some(val.parse()?),
+            "zk_synced_non_voting_followers" => {
+                self.synced_non_voting_followers = Some(val.parse()?)
+            }
+            "zk_synced_observers" => self.synced_observers = Some(val.parse()?),
+            // proposals
+            "zk_last_proposal_size" => self.last_proposal_size = Some(val.parse()?),
+            "zk_max_proposal_size" => self.max_proposal_size = Some(val.parse()?),
+            "zk_min_proposal_size" => self.min_proposal_size = Some(val.parse()?),
+            // client responses
+            "zk_last_client_response_size" => self.last_client_response_size = Some(val.parse()?),
+            "zk_max_client_response_size" => self.max_client_response_size = Some(val.parse()?),
+            "zk_min_client_response_size" => self.min_client_response_size = Some(val.parse()?),
+            // errors
+            "zk_digest_mismatches_count" => self.digest_mismatches_count = Some(val.parse()?),
+            "zk_unrecoverable_error_count" => self.unrecoverable_error_count = Some(val.parse()?),
+            // quorum
+            "zk_quorum_size" => self.quorum.quorum_size = Some(val.parse()?),
+            "zk_looking_count" => self.quorum.looking_count = Some(val.parse()?),
+            "zk_leader_uptime" => self.quorum.leader_uptime = Some(val.parse()?),
+            "zk_quit_leading_due_to_disloyal_voter" => {
+                self.quorum.quit_leading_due_to_disloyal_voter = Some(val.parse()?)
+            }
+            "zk_diff_count" => self.quorum.diff_count = Some(val.parse()?),
+            "zk_snap_count" => self.quorum.snap_count = Some(val.parse()?),
+            "zk_proposal_count" => self.quorum.proposal_count = Some(val.parse()?),
+            "zk_commit_count" => self.quorum.commit_count = Some(val.parse()?),
+            "zk_learner_proposal_received_count" => {
+                self.quorum.learner_proposal_received_count = Some(val.parse()?)
+            }
+            "zk_learner_commit_received_count" => {
+                self.quorum.learner_commit_received_count = Some(val.parse()?)
+            }
+            // prep processor
+            "zk_prep_processor_request_queued" => {
+                self.prep_processor.prep_processor_request_queued = Some(val.parse()?)
+            }
+            "zk_outstanding_changes_queued" => {
+                self.prep_processor.outstanding_changes_queued = Some(val.parse()?)
+            }
+            "zk_outstanding_changes_removed" => {
+                self.prep_processor.outstanding_changes_removed = Some(val.parse()?)
+            }
+            // commit processor
+            "zk_request_commit_queued" => {
+                self.commit_processor.request_commit_queued = Some(val.parse()?)
+            }
+            // sync processor
+            "zk_sync_processor_request_queued" => {
+                self.sync_processor.sync_processor_request_queued = Some(val.parse()?)
+            }
+            // requests
+            "zk_stale_requests" => self.requests.stale_requests = Some(val.parse()?),
+            "zk_stale_requests_dropped" => {
+                self.requests.stale_requests_dropped = Some(val.parse()?)
+            }
+            "zk_stale_replies" => self.requests.stale_replies = Some(val.parse()?),
+            "zk_large_requests_rejected" => {
+                self.requests.large_requests_rejected = Some(val.parse()?)
+            }
+            "zk_request_throttle_wait_count" => {
+                self.requests.request_throttle_wait_count = Some(val.parse()?)
+            }
+            // sessions
+            "zk_global_sessions" => self.sessions.global_sessions = Some(val.parse()?),
+            "zk_local_sessions" => self.sessions.local_sessions = Some(val.parse()?),
+            "zk_stale_sessions_expired" => {
+                self.sessions.stale_sessions_expired = Some(val.parse()?)
+            }
+            "zk_revalidate_count" => self.sessions.revalidate_count = Some(val.parse()?),
+            // tls
+            "zk_outstanding_tls_handshake" => self.tls.outstanding_handshake = Some(val.parse()?),
+            "zk_tls_handshake_exceeded" => self.tls.handshake_exceeded = Some(val.parse()?),
+            // ensemble auth
+            "zk_ensemble_auth_success" => self.ensemble_auth.success = Some(val.parse()?),
+            "zk_ensemble_auth_fail" => self.ensemble_auth.fail = Some(val.parse()?),
+            "zk_ensemble_auth_skip" => self.ensemble_auth.skip = Some(val.parse()?),
+            // caches
+            "zk_response_packet_cache_hits" => {
+                self.caches.response_packet_cache_hits = Some(val.parse()?)
+            }
+            "zk_response_packet_cache_misses" => {
+                self.caches.response_packet_cache_misses = Some(val.parse()?)
+            }
+            "zk_response_packet_get_children_cache_hits" => {
+                self.caches.response_packet_get_children_cache_hits = Some(val.parse()?)
+            }
+            "zk_response_packet_get_children_cache_misses" => {
+                self.caches.response_packet_get_children_cache_misses = Some(val.parse()?)
+            }
+            _ => {
+                self.misc.insert(key.into(), val.into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Maps a "summary" metric to the corresponding field
+    ///
+    /// Returns the sample back if the metric is unknown.
+    fn map_metric_sample(
+        &mut self,
+        name: &str,
+        sample: ZK4LWMetricSample,
+    ) -> Option<ZK4LWMetricSample> {
+        if name == LATENCY {
+            self.latency = sample;
+            return None;
+        }
+
+        let field = match name {
+            // connections
+            "connection_token_deficit" => Some(&mut self.connection_token_deficit),
+            "netty_queued_buffer_capacity" => Some(&mut self.netty_queued_buffer_capacity),
+            // quorum
+            "election_time" => Some(&mut self.quorum.election_time),
+            "follower_sync_time" => Some(&mut self.quorum.follower_sync_time),
+            // prep processor
+            "prep_processor_queue_size" => Some(&mut self.prep_processor.prep_processor_queue_size),
+            "prep_process_time" => Some(&mut self.prep_processor.prep_process_time),
+            // commit processor
+            "read_commit_proc_req_queued" => {
+                Some(&mut self.commit_processor.read_commit_proc_req_queued)
+            }
+            "write_commit_proc_req_queued" => {
+                Some(&mut self.commit_processor.write_commit_proc_req_queued)
+            }
+            "commit_commit_proc_req_queued" => {
+                Some(&mut self.commit_processor.commit_commit_proc_req_queued)
+            }
+            "read_commit_proc_issued" => Some(&mut self.commit_processor.read_commit_proc_issued),
+            "write_commit_proc_issued" => Some(&mut self.commit_processor.write_commit_proc_issued),
+            "session_queues_drained" => Some(&mut self.commit_processor.session_queues_drained),
+            "pending_session_queue_size" => {
+                Some(&mut self.commit_processor.pending_session_queue_size)
+            }
+            "requests_in_session_queue" => {
+                Some(&mut self.commit_processor.requests_in_session_queue)
+            }
+            "reads_issued_from_session_queue" => {
+                Some(&mut self.commit_processor.reads_issued_from_session_queue)
+            }
+            "reads_after_write_in_session_queue" => {
+                Some(&mut self.commit_processor.reads_after_write_in_session_queue)
+            }
+            "commit_process_time" => Some(&mut self.commit_processor.commit_process_time),
+            "concurrent_request_processing_in_commit_processor" => Some(
+                &mut self
+                    .commit_processor
+                    .concurrent_request_processing_in_commit_processor,
+            ),
+            "time_waiting_empty_pool_in_commit_processor_read_ms" => Some(
+                &mut self
+                    .commit_processor
+                    .time_waiting_empty_pool_in_commit_processor_read_ms,
+            ),
+            "write_batch_time_in_commit_processor" => {
+                Some(&mut self.commit_processor.write_batch_time_in_commit_processor)
+            }
+            // sync processor
+            "sync_processor_queue_size" => Some(&mut self.sync_processor.sync_processor_queue_size),
+            "sync_processor_batch_size" => Some(&mut self.sync_processor.sync_processor_batch_size),
+            "sync_process_time" => Some(&mut self.sync_processor.sync_process_time),
+            "fsynctime" => Some(&mut self.sync_processor.fsynctime),
+            "snapshottime" => Some(&mut self.sync_processor.snapshottime),
+            // watch events
+            "node_created_watch_count" => Some(&mut self.watch_events.node_created_watch_count),
+            "node_deleted_watch_count" => Some(&mut self.watch_events.node_deleted_watch_count),
+            "node_changed_watch_count" => Some(&mut self.watch_events.node_changed_watch_count),
+            "node_children_watch_count" => Some(&mut self.watch_events.node_children_watch_count),
+            // startup
+            "dbinittime" => Some(&mut self.startup.dbinittime),
+            "startup_snap_load_time" => Some(&mut self.startup.startup_snap_load_time),
+            "startup_txns_load_time" => Some(&mut self.startup.startup_txns_load_time),
+            "startup_txns_loaded" => Some(&mut self.startup.startup_txns_loaded),
+            _ => None,
+        };
+        if let Some(field) = field {
+            *field = Some(sample);
+            return None;
+        }
+
+        // NOTE: Some metrics are reported once per learner (i.e. `zk_<stat>_<server id>_<name>`),
+        // or once per namespace (i.e. `zk_<stat>_<top level znode>_<name>`)
+        if let Some(Ok(sid)) = name
+            .strip_suffix(LEARNER_HANDLER_QP_SIZE_SUFFIX)
+            .map(str::parse)
+        {
+            self.quorum.learner_handler_qp_size.insert(sid, sample);
+            return None;
+        }
+        if let Some(namespace) = name.strip_suffix(WRITE_PER_NAMESPACE_SUFFIX) {
+            self.write_per_namespace.insert(namespace.into(), sample);
+            return None;
+        }
+
+        Some(sample)
+    }
+
+    /// Maps a "summary" metric with percentiles to the corresponding field
+    ///
+    /// Returns the sample back if the metric is unknown.
+    fn map_time_metric_sample(
+        &mut self,
+        name: &str,
+        time_sample: ZK4LWTimeMetricSample,
+    ) -> Option<ZK4LWTimeMetricSample> {
+        let field = match name {
+            // quorum
+            "proposal_latency" => Some(&mut self.quorum.proposal_latency),
+            "proposal_ack_creation_latency" => Some(&mut self.quorum.proposal_ack_creation_latency),
+            "quorum_ack_latency" => Some(&mut self.quorum.quorum_ack_latency),
+            "commit_propagation_latency" => Some(&mut self.quorum.commit_propagation_latency),
+            "propagation_latency" => Some(&mut self.quorum.propagation_latency),
+            "om_proposal_process_time_ms" => Some(&mut self.quorum.om_proposal_process_time_ms),
+            "om_commit_process_time_ms" => Some(&mut self.quorum.om_commit_process_time_ms),
+            // prep processor
+            "prep_processor_queue_time_ms" => {
+                Some(&mut self.prep_processor.prep_processor_queue_time_ms)
+            }
+            "close_session_prep_time" => Some(&mut self.prep_processor.close_session_prep_time),
+            // commit processor
+            "read_commitproc_time_ms" => Some(&mut self.commit_processor.read_commitproc_time_ms),
+            "write_commitproc_time_ms" => Some(&mut self.commit_processor.write_commitproc_time_ms),
+            "local_write_committed_time_ms" => {
+                Some(&mut self.commit_processor.local_write_committed_time_ms)
+            }
+            "server_write_committed_time_ms" => {
+                Some(&mut self.commit_processor.server_write_committed_time_ms)
+            }
+            // sync processor
+            "sync_processor_queue_time_ms" => {
+                Some(&mut self.sync_processor.sync_processor_queue_time_ms)
+            }
+            "sync_processor_queue_flush_time_ms" => {
+                Some(&mut self.sync_processor.sync_processor_queue_flush_time_ms)
+            }
+            "sync_processor_queue_and_flush_time_ms" => {
+                Some(&mut self.sync_processor.sync_processor_queue_and_flush_time_ms)
+            }
+            // requests
+            "readlatency" => Some(&mut self.requests.readlatency),
+            "updatelatency" => Some(&mut self.requests.updatelatency),
+            "read_final_proc_time_ms" => Some(&mut self.requests.read_final_proc_time_ms),
+            "write_final_proc_time_ms" => Some(&mut self.requests.write_final_proc_time_ms),
+            // watch events
+            "dead_watchers_cleaner_latency" => {
+                Some(&mut self.watch_events.dead_watchers_cleaner_latency)
+            }
+            _ => None,
+        };
+        if let Some(field) = field {
+            *field = Some(time_sample);
+            return None;
+        }
+
+        if let Some(Ok(sid)) = name
+            .strip_suffix(LEARNER_HANDLER_QP_TIME_SUFFIX)
+            .map(str::parse)
+        {
+            self.quorum
+                .learner_handler_qp_time_ms
+                .insert(sid, time_sample);
+            return None;
+        }
+
+        Some(time_sample)
+    }
 }
 
 /// The Monitor (i.e. "mntr") command
+#[derive(Default)]
 pub struct ZK4LWMonitor;
 
 impl ZK4LWCommand for ZK4LWMonitor {
     type Response = ZK4LWMonitorResponse;
 
-    fn request_body() -> &'static str {
-        COMMAND
-    }
+    const NAME: &'static str = COMMAND;
 
     fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        Self::build_response_with_parser(response_body, &mut ZK4LWParser::default())
+    }
+
+    fn build_response_with_parser(
+        response_body: &str,
+        parser: &mut ZK4LWParser,
+    ) -> ZK4LWResult<Self::Response> {
         // Parse response body into key/value pairs
         let response_map = tab_separated_bytes_to_key_value(response_body)?;
+        parser.require_fields(&response_map, REQUIRED_FIELDS)?;
 
-        // Map by key to a specific field in the response
+        // Reassemble "summary" metrics (e.g. `zk_avg_fsynctime`, `zk_max_fsynctime`, ...) and
+        // map them by name to a specific field in the response
+        let (summaries, response_map) = key_value_to_summary_metrics(response_map, parser)?;
         let mut response = ZK4LWMonitorResponse::default();
-        for (key, val) in response_map.into_iter() {
-            match key {
-                // NOTE: `zk_version` is too "dense" with details,
-                // so we split it into more useful, separate parts
-                "zk_version" => {
-                    // Extract the 'version'
-                    let version_split: Vec<&str> =
-                        val.split("-").filter(|x| !x.is_empty()).collect();
-                    if version_split.len() != 2 {
-                        return Err(ZK4LWError::ParseStringError(format!(
-                            "Unable to parse version from string: '{}'",
-                            val
-                        )));
-                    }
-                    response.version = version_split.get(0).unwrap().trim().to_string();
-
-                    // Extract the 'build revision' and 'build date'
-                    let build_split: Vec<&str> = version_split.get(1).unwrap().split(",").collect();
-                    if build_split.len() != 2 {
-                        return Err(ZK4LWError::ParseStringError(format!(
-                            "Unable to parse build from string: '{}'",
-                            val
-                        )));
-                    }
-                    response.build_revision = build_split.get(0).unwrap().trim().to_string();
-                    response.build_date = build_split.get(1).unwrap().trim().to_string();
-                }
-                // latency
-                "zk_avg_latency" => response.latency.avg = val.parse()?,
-                "zk_max_latency" => response.latency.max = val.parse()?,
-                "zk_min_latency" => response.latency.min = val.parse()?,
-                // packets
-                "zk_packets_received" => response.packets_received = val.parse()?,
-                "zk_packets_sent" => response.packets_sent = val.parse()?,
-                // connections
-                "zk_num_alive_connections" => response.num_alive_connections = val.parse()?,
-                "zk_connection_drop_count" => response.connection_drop_count = Some(val.parse()?),
-                "zk_connection_drop_probability" => {
-                    response.connection_drop_probability = Some(val.parse()?)
-                }
-                "zk_connection_rejected" => response.connection_rejected = Some(val.parse()?),
-                "zk_connection_request_count" => {
-                    response.connection_request_count = Some(val.parse()?)
-                }
-                "zk_connection_revalidate_count" => {
-                    response.connection_revalidate_count = Some(val.parse()?)
-                }
-                "zk_sessionless_connections_expired" => {
-                    response.sessionless_connections_expired = Some(val.parse()?)
-                }
-                // watchers
-                "zk_add_dead_watcher_stall_time" => {
-                    response.add_dead_watcher_stall_time = Some(val.parse()?)
-                }
-                "zk_dead_watchers_cleared" => response.dead_watchers_cleared = Some(val.parse()?),
-                "zk_dead_watchers_queued" => response.dead_watchers_queued = Some(val.parse()?),
-                // requests
-                "zk_outstanding_requests" => response.outstanding_requests = val.parse()?,
-                // state
-                "zk_server_state" => response.server_state = val.parse()?,
-                // znodes
-                "zk_znode_count" => response.znode_count = val.parse()?,
-                "zk_watch_count" => response.watch_count = val.parse()?,
-                "zk_ephemerals_count" => response.ephemerals_count = val.parse()?,
-                // data size
-                "zk_approximate_data_size" => response.approximate_data_size = val.parse()?,
-                // file descriptors
-                "zk_open_file_descriptor_count" => {
-                    response.open_file_descriptor_count = val.parse()?
-                }
-                "zk_max_file_descriptor_count" => {
-                    response.max_file_descriptor_count = val.parse()?
-                }
-                // followers
-                "zk_followers" => response.learners = Some(val.parse()?), //< NOTE: synonym of "zk_learners" in ZK < 3.6.x
-                "zk_learners" => response.learners = Some(val.parse()?),
-                "zk_synced_followers" => response.synced_followers = Some(val.parse()?),
-                "zk_pending_syncs" => response.pending_syncs = Some(val.parse()?),
-                "zk_synced_non_voting_followers" => response.synced_non_voting_followers = Some(val.parse()?),
-                "zk_synced_observers" => response.synced_observers = Some(val.parse()?),
-                // proposals
-                "zk_last_proposal_size" => response.last_proposal_size = Some(val.parse()?),
-                "zk_max_proposal_size" => response.max_proposal_size = Some(val.parse()?),
-                "zk_min_proposal_size" => response.min_proposal_size = Some(val.parse()?),
-                _ => {
-                    response.misc.insert(key.into(), val.into());
-                }
+        for (name, sample) in summaries.samples.into_iter() {
+            if let Some(sample) = response.map_metric_sample(&name, sample) {
+                response.misc_summaries.samples.insert(name, sample);
+            }
+        }
+        for (name, time_sample) in summaries.time_samples.into_iter() {
+            if let Some(time_sample) = response.map_time_metric_sample(&name, time_sample) {
+                response
+                    .misc_summaries
+                    .time_samples
+                    .insert(name, time_sample);
             }
         }
 
+        // Map by key to a specific field in the response
+        for (key, val) in response_map.into_iter() {
+            parser.field(key, response.map_field(key, val))?;
+        }
+
         Ok(response)
     }
 }
@@ -193,19 +696,25 @@ mod tests {
 
     use crate::client::ZK4LWCommand;
     use crate::commands::mntr::ZK4LWMonitor;
+    use crate::errors::ZK4LWError;
+    use crate::policy::{ZK4LWParsePolicy, ZK4LWParser};
     use crate::state::ZK4LWServerState::LEADER;
+    use crate::version::ZK4LWVersion;
 
     #[test]
     fn should_build_response_from_zk34_monitor_response_body() {
         let mntr_34_resp_body = fs::read_to_string("../../fixtures/3.4/mntr.response").unwrap();
         let mntr_34_resp = ZK4LWMonitor::build_response(mntr_34_resp_body.as_str()).unwrap();
 
-        assert_eq!(mntr_34_resp.version, "3.4.14");
+        assert_eq!(mntr_34_resp.version, ZK4LWVersion::new(3, 4, 14));
         assert_eq!(
-            mntr_34_resp.build_revision,
-            "4c25d480e66aadd371de8bd2fd8da255ac140bcf"
+            mntr_34_resp.version.revision.as_deref(),
+            Some("4c25d480e66aadd371de8bd2fd8da255ac140bcf")
+        );
+        assert_eq!(
+            mntr_34_resp.version.build_date.as_deref(),
+            Some("03/06/2019 16:18 GMT")
         );
-        assert_eq!(mntr_34_resp.build_date, "built on 03/06/2019 16:18 GMT");
         assert_eq!(mntr_34_resp.latency.avg, 0.0);
         assert_eq!(mntr_34_resp.latency.min, 0);
         assert_eq!(mntr_34_resp.latency.max, 0);
@@ -241,12 +750,15 @@ mod tests {
         let mntr_35_resp_body = fs::read_to_string("../../fixtures/3.5/mntr.response").unwrap();
         let mntr_35_resp = ZK4LWMonitor::build_response(mntr_35_resp_body.as_str()).unwrap();
 
-        assert_eq!(mntr_35_resp.version, "3.5.8");
+        assert_eq!(mntr_35_resp.version, ZK4LWVersion::new(3, 5, 8));
         assert_eq!(
-            mntr_35_resp.build_revision,
-            "f439ca583e70862c3068a1f2a7d4d068eec33315"
+            mntr_35_resp.version.revision.as_deref(),
+            Some("f439ca583e70862c3068a1f2a7d4d068eec33315")
+        );
+        assert_eq!(
+            mntr_35_resp.version.build_date.as_deref(),
+            Some("05/04/2020 15:07 GMT")
         );
-        assert_eq!(mntr_35_resp.build_date, "built on 05/04/2020 15:07 GMT");
         assert_eq!(mntr_35_resp.latency.avg, 0.0);
         assert_eq!(mntr_35_resp.latency.min, 0);
         assert_eq!(mntr_35_resp.latency.max, 0);
@@ -275,12 +787,15 @@ mod tests {
         let mntr_36_resp_body = fs::read_to_string("../../fixtures/3.6/mntr.response").unwrap();
         let mntr_36_resp = ZK4LWMonitor::build_response(mntr_36_resp_body.as_str()).unwrap();
 
-        assert_eq!(mntr_36_resp.version, "3.6.1");
+        assert_eq!(mntr_36_resp.version, ZK4LWVersion::new(3, 6, 1));
+        assert_eq!(
+            mntr_36_resp.version.revision.as_deref(),
+            Some("104dcb3e3fb464b30c5186d229e00af9f332524b")
+        );
         assert_eq!(
-            mntr_36_resp.build_revision,
-            "104dcb3e3fb464b30c5186d229e00af9f332524b"
+            mntr_36_resp.version.build_date.as_deref(),
+            Some("04/21/2020 15:01 GMT")
         );
-        assert_eq!(mntr_36_resp.build_date, "built on 04/21/2020 15:01 GMT");
         assert_eq!(mntr_36_resp.latency.avg, 0.0);
         assert_eq!(mntr_36_resp.latency.min, 0);
         assert_eq!(mntr_36_resp.latency.max, 0);
@@ -303,7 +818,146 @@ mod tests {
         assert_eq!(mntr_36_resp.last_proposal_size.unwrap(), -1);
         assert_eq!(mntr_36_resp.min_proposal_size.unwrap(), -1);
         assert_eq!(mntr_36_resp.max_proposal_size.unwrap(), -1);
-        // TODO lots of fields to add:
-        // assert_eq!(mntr_36_resp.misc.len(), 0);
+        assert_eq!(mntr_36_resp.uptime.unwrap(), 91405);
+        assert_eq!(mntr_36_resp.peer_state.unwrap(), "leading - broadcast");
+        assert_eq!(mntr_36_resp.bytes_received_count.unwrap(), 16);
+        assert_eq!(mntr_36_resp.last_client_response_size.unwrap(), -1);
+        assert_eq!(mntr_36_resp.quorum.quorum_size.unwrap(), 3);
+        assert_eq!(mntr_36_resp.quorum.leader_uptime.unwrap(), 91227);
+        assert_eq!(mntr_36_resp.quorum.diff_count.unwrap(), 3);
+        assert_eq!(mntr_36_resp.quorum.snap_count.unwrap(), 1);
+        let election_time = mntr_36_resp.quorum.election_time.unwrap();
+        assert_eq!(election_time.avg, 399.0);
+        assert_eq!(election_time.max, 399);
+        assert_eq!(election_time.count.unwrap(), 1);
+        assert_eq!(mntr_36_resp.quorum.learner_handler_qp_size.len(), 4);
+        let learner_qp_size = mntr_36_resp
+            .quorum
+            .learner_handler_qp_size
+            .get(&51)
+            .unwrap();
+        assert_eq!(learner_qp_size.avg, 0.0319);
+        assert_eq!(learner_qp_size.sum.unwrap(), 3);
+        let learner_qp_time = mntr_36_resp
+            .quorum
+            .learner_handler_qp_time_ms
+            .get(&10)
+            .unwrap();
+        assert_eq!(learner_qp_time.p50.unwrap(), 20);
+        assert_eq!(learner_qp_time.p999.unwrap(), 20);
+        let quorum_ack_latency = mntr_36_resp.quorum.quorum_ack_latency.unwrap();
+        assert_eq!(quorum_ack_latency.p95.unwrap(), 0);
+        assert_eq!(quorum_ack_latency.p99.unwrap(), 0);
+        assert_eq!(
+            mntr_36_resp
+                .sync_processor
+                .snapshottime
+                .unwrap()
+                .sum
+                .unwrap(),
+            5
+        );
+        assert_eq!(mntr_36_resp.startup.dbinittime.unwrap().min, 52);
+        assert_eq!(mntr_36_resp.ensemble_auth.success.unwrap(), 0);
+        assert_eq!(mntr_36_resp.tls.outstanding_handshake.unwrap(), 0);
+        assert_eq!(mntr_36_resp.caches.response_packet_cache_hits.unwrap(), 0);
+        assert!(mntr_36_resp.watch_events.node_changed_watch_count.is_some());
+        assert_eq!(
+            mntr_36_resp
+                .write_per_namespace
+                .get("zookeeper")
+                .unwrap()
+                .avg,
+            270.0
+        );
+        assert_eq!(mntr_36_resp.misc.len(), 0);
+        assert!(mntr_36_resp.misc_summaries.is_empty());
+    }
+
+    #[test]
+    fn should_keep_unknown_summary_metrics_in_misc_summaries() {
+        // NOTE: lenient, as the response lacks the required fields
+        let mut parser = ZK4LWParser::new(ZK4LWParsePolicy::LENIENT);
+        let mntr_resp = ZK4LWMonitor::build_response_with_parser(
+            "zk_avg_fsynctime\t1.5\n\
+             zk_avg_unknown_metric\t2.0\n\
+             zk_max_unknown_metric\t3\n\
+             zk_avg_unknown_time_metric\t0.5\n\
+             zk_p99_unknown_time_metric\t1\n",
+            &mut parser,
+        )
+        .unwrap();
+
+        assert_eq!(mntr_resp.sync_processor.fsynctime.unwrap().avg, 1.5);
+        assert_eq!(mntr_resp.misc.len(), 0);
+        assert_eq!(mntr_resp.misc_summaries.len(), 2);
+        assert_eq!(
+            mntr_resp
+                .misc_summaries
+                .samples
+                .get("unknown_metric")
+                .unwrap()
+                .max,
+            3
+        );
+        assert_eq!(
+            mntr_resp
+                .misc_summaries
+                .time_samples
+                .get("unknown_time_metric")
+                .unwrap()
+                .p99,
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn should_keep_going_on_bad_summary_metrics_if_lenient() {
+        let mntr_34_resp_body = fs::read_to_string("../../fixtures/3.4/mntr.response")
+            .unwrap()
+            .replace("zk_avg_latency\t0", "zk_avg_latency\tbad")
+            .replace("zk_max_latency\t0", "zk_max_latency\t7");
+
+        match ZK4LWMonitor::build_response(mntr_34_resp_body.as_str()) {
+            Err(ZK4LWError::ParseFloatError(_)) => {}
+            _ => panic!("Expected ParseFloatError"),
+        }
+
+        let mut parser = ZK4LWParser::new(ZK4LWParsePolicy::LENIENT);
+        let mntr_34_resp =
+            ZK4LWMonitor::build_response_with_parser(mntr_34_resp_body.as_str(), &mut parser)
+                .unwrap();
+        assert_eq!(mntr_34_resp.latency.avg, 0.0);
+        assert_eq!(mntr_34_resp.latency.max, 7);
+        assert_eq!(mntr_34_resp.znode_count, 4);
+
+        let warnings = parser.into_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "zk_avg_latency");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_serialize_and_deserialize_response() {
+        use crate::commands::mntr::ZK4LWMonitorResponse;
+
+        let mntr_36_resp_body = fs::read_to_string("../../fixtures/3.6/mntr.response").unwrap();
+        let mntr_36_resp = ZK4LWMonitor::build_response(mntr_36_resp_body.as_str()).unwrap();
+
+        let json = serde_json::to_value(&mntr_36_resp).unwrap();
+        assert_eq!(json["server_state"], "leader");
+        assert_eq!(json["znode_count"], 5);
+        assert_eq!(json["version"]["major"], 3);
+        assert_eq!(json["latency"]["avg"], 0.0);
+        assert_eq!(json["quorum"]["learner_commit_received_count"], 0);
+
+        let mntr_36_resp_de: ZK4LWMonitorResponse = serde_json::from_value(json).unwrap();
+        assert!(mntr_36_resp_de.server_state == LEADER);
+        assert_eq!(mntr_36_resp_de.version, mntr_36_resp.version);
+        assert_eq!(mntr_36_resp_de.znode_count, mntr_36_resp.znode_count);
+        assert_eq!(
+            mntr_36_resp_de.misc_summaries.len(),
+            mntr_36_resp.misc_summaries.len()
+        );
     }
 }
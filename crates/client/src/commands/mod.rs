@@ -7,4 +7,17 @@
 //! [3.6](https://zookeeper.apache.org/doc/r3.6.1/zookeeperAdmin.html#sc_4lw)
 
 pub mod common;
+pub mod conf;
+pub mod cons;
+pub mod dirs;
+pub mod dump;
+pub mod envi;
+pub mod hash;
+pub mod isro;
 pub mod mntr;
+pub mod reset;
+pub mod ruok;
+pub mod srvr;
+pub mod stat;
+pub mod tracemask;
+pub mod watches;
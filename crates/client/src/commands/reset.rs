@@ -0,0 +1,105 @@
+//! The 4LW Reset commands. Also known as "srst" and "crst".
+//!
+//! These commands reset statistics on the server:
+//!
+//! * `srst`: resets the server statistics
+//! * `crst`: resets the connection/session statistics for all connections
+//!
+//! NOTE: These commands change the state of the server,
+//! so they are marked as `ZK4LWCommand::MUTATING`.
+//!
+//! Available since: ZooKeeper 3.3.0
+
+use crate::{client::*, errors::*, result::*};
+
+const COMMAND_RESET_STATS: &str = "srst";
+const COMMAND_RESET_CONNECTION_STATS: &str = "crst";
+
+const RESPONSE_RESET_STATS: &str = "Server stats reset.";
+const RESPONSE_RESET_CONNECTION_STATS: &str = "Connection stats reset.";
+
+fn verify_acknowledgement(response_body: &str, expected: &str) -> ZK4LWResult<()> {
+    if response_body.trim() == expected {
+        Ok(())
+    } else {
+        Err(ZK4LWError::UnexpectedResponseError(
+            response_body.trim().to_string(),
+        ))
+    }
+}
+
+/// The Reset Statistics (i.e. "srst") command
+#[derive(Default)]
+pub struct ZK4LWResetStats;
+
+impl ZK4LWCommand for ZK4LWResetStats {
+    type Response = ();
+
+    const NAME: &'static str = COMMAND_RESET_STATS;
+    const MUTATING: bool = true;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        verify_acknowledgement(response_body, RESPONSE_RESET_STATS)
+    }
+}
+
+/// The Reset Connection Statistics (i.e. "crst") command
+#[derive(Default)]
+pub struct ZK4LWResetConnectionStats;
+
+impl ZK4LWCommand for ZK4LWResetConnectionStats {
+    type Response = ();
+
+    const NAME: &'static str = COMMAND_RESET_CONNECTION_STATS;
+    const MUTATING: bool = true;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        verify_acknowledgement(response_body, RESPONSE_RESET_CONNECTION_STATS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::client::ZK4LWCommand;
+    use crate::commands::mntr::ZK4LWMonitor;
+    use crate::commands::reset::{ZK4LWResetConnectionStats, ZK4LWResetStats};
+    use crate::errors::ZK4LWError;
+
+    #[test]
+    fn should_build_response_from_reset_response_bodies() {
+        for version in ["3.4", "3.5", "3.6"].iter() {
+            let srst_resp_body =
+                fs::read_to_string(format!("../../fixtures/{}/srst.response", version)).unwrap();
+            assert!(ZK4LWResetStats::build_response(srst_resp_body.as_str()).is_ok());
+
+            let crst_resp_body =
+                fs::read_to_string(format!("../../fixtures/{}/crst.response", version)).unwrap();
+            assert!(ZK4LWResetConnectionStats::build_response(crst_resp_body.as_str()).is_ok());
+        }
+    }
+
+    #[test]
+    fn should_fail_to_build_response_from_unexpected_reset_response_bodies() {
+        match ZK4LWResetStats::build_response("Connection stats reset.\n") {
+            Err(ZK4LWError::UnexpectedResponseError(body)) => {
+                assert_eq!(body, "Connection stats reset.")
+            }
+            _ => panic!("Expected UnexpectedResponseError"),
+        }
+        assert!(ZK4LWResetConnectionStats::build_response("").is_err());
+    }
+
+    #[test]
+    fn should_mark_reset_commands_as_mutating() {
+        assert_eq!(
+            [
+                ZK4LWResetStats::MUTATING,
+                ZK4LWResetConnectionStats::MUTATING,
+                ZK4LWMonitor::MUTATING,
+            ],
+            [true, true, false]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! The 4LW "Are you OK?" command. Also known as "ruok".
+//!
+//! This command tests if the server is running in a non-error state:
+//! the server will respond with "imok" if it is running,
+//! otherwise it will not respond at all.
+//!
+//! NOTE: A response of "imok" does not necessarily indicate that the server has joined the quorum,
+//! just that the server process is active and bound to the specified client port.
+//!
+//! Available since: ZooKeeper 3.3.0
+
+use crate::{client::*, result::*};
+
+const COMMAND: &str = "ruok";
+const RESPONSE_IMOK: &str = "imok";
+
+/// Response to the `ruok` command
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum ZK4LWRuokResponse {
+    /// The server is running in a non-error state
+    IMOK,
+    /// The server closed the connection without responding
+    EMPTY,
+    /// The server responded with something other than "imok"
+    UNEXPECTED(String),
+}
+
+impl ZK4LWRuokResponse {
+    /// Returns `true` only if the server responded "imok"
+    pub fn is_ok(&self) -> bool {
+        *self == ZK4LWRuokResponse::IMOK
+    }
+}
+
+/// The "Are you OK?" (i.e. "ruok") command
+///
+/// This is the cheapest command to use as a liveness probe: the client stops
+/// reading as soon as "imok" is received, without waiting for the server to close the connection.
+#[derive(Default)]
+pub struct ZK4LWRuok;
+
+impl ZK4LWCommand for ZK4LWRuok {
+    type Response = ZK4LWRuokResponse;
+
+    const NAME: &'static str = COMMAND;
+
+    fn is_response_complete(partial_response_body: &[u8]) -> bool {
+        partial_response_body == RESPONSE_IMOK.as_bytes()
+    }
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        Ok(match response_body.trim() {
+            RESPONSE_IMOK => ZK4LWRuokResponse::IMOK,
+            "" => ZK4LWRuokResponse::EMPTY,
+            unexpected => ZK4LWRuokResponse::UNEXPECTED(unexpected.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::client::ZK4LWCommand;
+    use crate::commands::ruok::{ZK4LWRuok, ZK4LWRuokResponse};
+
+    #[test]
+    fn should_build_response_from_ruok_response_body() {
+        let ruok_resp = ZK4LWRuok::build_response("imok").unwrap();
+        assert_eq!(ruok_resp, ZK4LWRuokResponse::IMOK);
+        assert!(ruok_resp.is_ok());
+
+        let ruok_resp = ZK4LWRuok::build_response("").unwrap();
+        assert_eq!(ruok_resp, ZK4LWRuokResponse::EMPTY);
+        assert!(!ruok_resp.is_ok());
+
+        let ruok_resp = ZK4LWRuok::build_response("ruok").unwrap();
+        assert_eq!(ruok_resp, ZK4LWRuokResponse::UNEXPECTED("ruok".into()));
+        assert!(!ruok_resp.is_ok());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_serialize_and_deserialize_response() {
+        let responses = [
+            (ZK4LWRuokResponse::IMOK, serde_json::json!("imok")),
+            (ZK4LWRuokResponse::EMPTY, serde_json::json!("empty")),
+            (
+                ZK4LWRuokResponse::UNEXPECTED("ruok".into()),
+                serde_json::json!({ "unexpected": "ruok" }),
+            ),
+        ];
+        for (ruok_resp, json) in responses.iter() {
+            assert_eq!(&serde_json::to_value(ruok_resp).unwrap(), json);
+            let ruok_resp_de: ZK4LWRuokResponse = serde_json::from_value(json.clone()).unwrap();
+            assert_eq!(&ruok_resp_de, ruok_resp);
+        }
+    }
+}
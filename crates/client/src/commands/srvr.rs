@@ -0,0 +1,261 @@
+//! The 4LW Server command. Also known as "srvr".
+//!
+//! This command outputs full details for the server.
+//! On ZooKeeper 3.4.x this is the only way to read the last processed Zxid.
+//!
+//! Available since: ZooKeeper 3.3.0
+
+use std::collections::HashMap;
+
+use crate::{
+    client::*, commands::common::*, parsing::*, policy::*, result::*, state::*, version::*, zxid::*,
+};
+
+const COMMAND: &str = "srvr";
+
+/// Fields always present in the response (i.e. enforced by `ZK4LWParsePolicy::STRICT`)
+const REQUIRED_FIELDS: &[&str] = &[
+    "Zookeeper version",
+    "Latency min/avg/max",
+    "Received",
+    "Sent",
+    "Connections",
+    "Outstanding",
+    "Zxid",
+    "Mode",
+    "Node count",
+];
+
+/// Response to the `srvr` command
+///
+/// The fields are "divided" into 3 "classes":
+///
+/// * mapped & always present: they will always be in the response,
+///   and the value will always be valid
+/// * mapped & sometimes present: they will always be in the response,
+///   but their value is an `Option` that will be set to `None` if missing -
+///   a value can be missing because the node is not a "Leader"
+/// * unmapped: when a value hasn't been mapped (yet), its stored in the
+///   `misc` field, that is an hash-map - the idea is that we then release
+///   an update that maps it
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWServerResponse {
+    // version
+    pub version: ZK4LWVersion,
+    // latency
+    pub latency: ZK4LWMetricSample,
+    // packets
+    pub packets_received: i64,
+    pub packets_sent: i64,
+    // connections
+    pub num_alive_connections: i64,
+    // requests
+    pub outstanding_requests: i64,
+    /// Last processed Zxid
+    pub zxid: ZK4LWZxid,
+    // state
+    pub server_state: ZK4LWServerState,
+    // znodes
+    pub znode_count: i64,
+    // proposals
+    pub last_proposal_size: Option<i64>,
+    pub min_proposal_size: Option<i64>,
+    pub max_proposal_size: Option<i64>,
+    // unknown/unmapped fields
+    pub misc: HashMap<String, String>,
+}
+
+impl ZK4LWServerResponse {
+    /// Maps a field of the response, by key
+    fn map_field(&mut self, key: &str, val: &str) -> ZK4LWResult<()> {
+        match key {
+            "Zookeeper version" => self.version = val.parse()?,
+            // latency
+            "Latency min/avg/max" => {
+                let (min, avg, max) = slash_separated_triplet(val)?;
+                self.latency = ZK4LWMetricSample::new(avg.parse()?, max.parse()?, min.parse()?);
+            }
+            // packets
+            "Received" => self.packets_received = val.parse()?,
+            "Sent" => self.packets_sent = val.parse()?,
+            // connections
+            "Connections" => self.num_alive_connections = val.parse()?,
+            // requests
+            "Outstanding" => self.outstanding_requests = val.parse()?,
+            "Zxid" => self.zxid = val.parse()?,
+            // state
+            "Mode" => self.server_state = val.parse()?,
+            // znodes
+            "Node count" => self.znode_count = val.parse()?,
+            // proposals
+            "Proposal sizes last/min/max" => {
+                let (last, min, max) = slash_separated_triplet(val)?;
+                self.last_proposal_size = Some(last.parse()?);
+                self.min_proposal_size = Some(min.parse()?);
+                self.max_proposal_size = Some(max.parse()?);
+            }
+            _ => {
+                self.misc.insert(key.into(), val.into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The Server (i.e. "srvr") command
+#[derive(Default)]
+pub struct ZK4LWServer;
+
+impl ZK4LWCommand for ZK4LWServer {
+    type Response = ZK4LWServerResponse;
+
+    const NAME: &'static str = COMMAND;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        Self::build_response_with_parser(response_body, &mut ZK4LWParser::default())
+    }
+
+    fn build_response_with_parser(
+        response_body: &str,
+        parser: &mut ZK4LWParser,
+    ) -> ZK4LWResult<Self::Response> {
+        // Parse response body into key/value pairs
+        let response_map = colon_separated_bytes_to_key_value(response_body)?;
+        parser.require_fields(&response_map, REQUIRED_FIELDS)?;
+
+        // Map by key to a specific field in the response
+        let mut response = ZK4LWServerResponse::default();
+        for (key, val) in response_map.into_iter() {
+            parser.field(key, response.map_field(key, val))?;
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::client::ZK4LWCommand;
+    use crate::commands::srvr::ZK4LWServer;
+    use crate::state::ZK4LWServerState::{FOLLOWER, LEADER, READ_ONLY};
+    use crate::version::ZK4LWVersion;
+    use crate::zxid::ZK4LWZxid;
+
+    #[test]
+    fn should_build_response_from_zk34_server_response_body() {
+        let srvr_34_resp_body = fs::read_to_string("../../fixtures/3.4/srvr.response").unwrap();
+        let srvr_34_resp = ZK4LWServer::build_response(srvr_34_resp_body.as_str()).unwrap();
+
+        assert_eq!(srvr_34_resp.version, ZK4LWVersion::new(3, 4, 14));
+        assert_eq!(
+            srvr_34_resp.version.revision.as_deref(),
+            Some("4c25d480e66aadd371de8bd2fd8da255ac140bcf")
+        );
+        assert_eq!(
+            srvr_34_resp.version.build_date.as_deref(),
+            Some("03/06/2019 16:18 GMT")
+        );
+        assert_eq!(srvr_34_resp.latency.avg, 0.0);
+        assert_eq!(srvr_34_resp.latency.min, 0);
+        assert_eq!(srvr_34_resp.latency.max, 0);
+        assert_eq!(srvr_34_resp.packets_received, 7);
+        assert_eq!(srvr_34_resp.packets_sent, 6);
+        assert_eq!(srvr_34_resp.num_alive_connections, 1);
+        assert_eq!(srvr_34_resp.outstanding_requests, 0);
+        assert_eq!(srvr_34_resp.zxid, ZK4LWZxid::new(1, 0));
+        assert_eq!(srvr_34_resp.server_state, LEADER);
+        assert_eq!(srvr_34_resp.znode_count, 4);
+        assert_eq!(srvr_34_resp.last_proposal_size.unwrap(), -1);
+        assert_eq!(srvr_34_resp.min_proposal_size.unwrap(), -1);
+        assert_eq!(srvr_34_resp.max_proposal_size.unwrap(), -1);
+        assert_eq!(srvr_34_resp.misc.len(), 0);
+    }
+
+    #[test]
+    fn should_build_response_from_zk35_server_response_body() {
+        let srvr_35_resp_body = fs::read_to_string("../../fixtures/3.5/srvr.response").unwrap();
+        let srvr_35_resp = ZK4LWServer::build_response(srvr_35_resp_body.as_str()).unwrap();
+
+        assert_eq!(srvr_35_resp.version, ZK4LWVersion::new(3, 5, 8));
+        assert_eq!(srvr_35_resp.packets_received, 5);
+        assert_eq!(srvr_35_resp.packets_sent, 4);
+        assert_eq!(srvr_35_resp.zxid, ZK4LWZxid::new(2, 0));
+        assert_eq!(srvr_35_resp.server_state, LEADER);
+        assert_eq!(srvr_35_resp.znode_count, 5);
+        assert_eq!(srvr_35_resp.max_proposal_size.unwrap(), -1);
+        assert_eq!(srvr_35_resp.misc.len(), 0);
+    }
+
+    #[test]
+    fn should_build_response_from_zk36_server_response_body() {
+        let srvr_36_resp_body = fs::read_to_string("../../fixtures/3.6/srvr.response").unwrap();
+        let srvr_36_resp = ZK4LWServer::build_response(srvr_36_resp_body.as_str()).unwrap();
+
+        assert_eq!(srvr_36_resp.version, ZK4LWVersion::new(3, 6, 1));
+        assert_eq!(
+            srvr_36_resp.version.revision.as_deref(),
+            Some("104dcb3e3fb464b30c5186d229e00af9f332524b")
+        );
+        assert_eq!(srvr_36_resp.latency.avg, 0.0);
+        assert_eq!(srvr_36_resp.packets_received, 6);
+        assert_eq!(srvr_36_resp.packets_sent, 11);
+        assert_eq!(srvr_36_resp.zxid, ZK4LWZxid::new(3, 0));
+        assert_eq!(srvr_36_resp.server_state, LEADER);
+        assert_eq!(srvr_36_resp.znode_count, 5);
+        assert_eq!(srvr_36_resp.misc.len(), 0);
+    }
+
+    #[test]
+    fn should_build_response_from_follower_server_response_body() {
+        let srvr_resp = ZK4LWServer::build_response(
+            "Zookeeper version: 3.5.8-f439ca583e70862c3068a1f2a7d4d068eec33315, built on 05/04/2020 15:07 GMT\n\
+             Latency min/avg/max: 1/2/10\n\
+             Received: 100\n\
+             Sent: 99\n\
+             Connections: 3\n\
+             Outstanding: 0\n\
+             Zxid: 0x2000001a4\n\
+             Mode: follower\n\
+             Node count: 42\n",
+        )
+        .unwrap();
+
+        assert_eq!(srvr_resp.latency.min, 1);
+        assert_eq!(srvr_resp.latency.avg, 2.0);
+        assert_eq!(srvr_resp.latency.max, 10);
+        assert_eq!(srvr_resp.zxid, ZK4LWZxid::new(2, 0x1a4));
+        assert_eq!(srvr_resp.server_state, FOLLOWER);
+        assert_eq!(srvr_resp.last_proposal_size, None);
+        assert_eq!(srvr_resp.misc.len(), 0);
+    }
+
+    #[test]
+    fn should_build_response_from_read_only_server_response_body() {
+        let srvr_resp = ZK4LWServer::build_response(
+            "Zookeeper version: 3.5.8-f439ca583e70862c3068a1f2a7d4d068eec33315, built on 05/04/2020 15:07 GMT\n\
+             Latency min/avg/max: 0/0/0\n\
+             Received: 12\n\
+             Sent: 11\n\
+             Connections: 1\n\
+             Outstanding: 0\n\
+             Zxid: 0xffffffffffffffff\n\
+             Mode: read-only\n\
+             Node count: 5\n",
+        )
+        .unwrap();
+
+        assert_eq!(srvr_resp.server_state, READ_ONLY);
+        assert!(!srvr_resp.server_state.tracks_sessions());
+        assert_eq!(srvr_resp.zxid, ZK4LWZxid(u64::MAX));
+        assert_eq!(format!("{:?}", srvr_resp.server_state), "read-only");
+        #[cfg(feature = "serde")]
+        assert_eq!(
+            serde_json::to_value(&srvr_resp.server_state).unwrap(),
+            "read-only"
+        );
+    }
+}
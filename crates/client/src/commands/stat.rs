@@ -0,0 +1,199 @@
+//! The 4LW Statistics command. Also known as "stat".
+//!
+//! This command outputs brief details for the server and the connected clients.
+//! It reports everything the `srvr` command does, plus the list of client connections.
+//!
+//! Available since: ZooKeeper 3.3.0
+
+use std::net::IpAddr;
+
+use crate::{client::*, commands::srvr::*, errors::*, parsing::*, policy::*, result::*};
+
+const COMMAND: &str = "stat";
+const CLIENTS_SECTION_HEADER: &str = "Clients:";
+
+/// Client connected to the server, as listed in the `Clients:` section of the response
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWStatClient {
+    pub address: IpAddr,
+    pub port: u16,
+    pub interest_ops: i64,
+    pub queued: i64,
+    pub received: i64,
+    pub sent: i64,
+}
+
+/// Response to the `stat` command
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWStatResponse {
+    /// Details of the server: the same as reported by the `srvr` command
+    pub server: ZK4LWServerResponse,
+    /// Clients connected to the server
+    pub clients: Vec<ZK4LWStatClient>,
+}
+
+/// The Statistics (i.e. "stat") command
+#[derive(Default)]
+pub struct ZK4LWStat;
+
+impl ZK4LWCommand for ZK4LWStat {
+    type Response = ZK4LWStatResponse;
+
+    const NAME: &'static str = COMMAND;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        Self::build_response_with_parser(response_body, &mut ZK4LWParser::default())
+    }
+
+    fn build_response_with_parser(
+        response_body: &str,
+        parser: &mut ZK4LWParser,
+    ) -> ZK4LWResult<Self::Response> {
+        let mut clients = Vec::new();
+        let mut server_lines = Vec::new();
+
+        // Separate the lines of the `Clients:` section (terminated by an empty line), from the rest
+        let mut in_clients_section = false;
+        for line in response_body.lines() {
+            if line.trim() == CLIENTS_SECTION_HEADER {
+                in_clients_section = true;
+            } else if in_clients_section && line.trim().is_empty() {
+                in_clients_section = false;
+            } else if in_clients_section {
+                // Clients that fail to parse are skipped, if the parser is lenient
+                match connection_line_to_client(line) {
+                    Ok(client) => clients.push(client),
+                    Err(error) => parser.field(line.trim(), Err(error))?,
+                }
+            } else {
+                server_lines.push(line);
+            }
+        }
+
+        Ok(ZK4LWStatResponse {
+            server: ZK4LWServer::build_response_with_parser(&server_lines.join("\n"), parser)?,
+            clients,
+        })
+    }
+}
+
+/// Parses a line of the `Clients:` section (e.g. `/172.18.0.1:59126[0](queued=0,recved=1,sent=0)`)
+fn connection_line_to_client(line: &str) -> ZK4LWResult<ZK4LWStatClient> {
+    let (address, interest_ops, stats) = connection_line_to_parts(line)?;
+    let stat = |key: &'static str| -> ZK4LWResult<i64> {
+        Ok(stats
+            .get(key)
+            .ok_or(ZK4LWError::MissingFieldError(key))?
+            .parse()?)
+    };
+
+    Ok(ZK4LWStatClient {
+        address: address.ip(),
+        port: address.port(),
+        interest_ops,
+        queued: stat("queued")?,
+        received: stat("recved")?,
+        sent: stat("sent")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, net::IpAddr};
+
+    use crate::client::ZK4LWCommand;
+    use crate::commands::stat::{ZK4LWStat, ZK4LWStatClient};
+    use crate::policy::{ZK4LWParsePolicy, ZK4LWParser};
+    use crate::state::ZK4LWServerState::LEADER;
+    use crate::version::ZK4LWVersion;
+    use crate::zxid::ZK4LWZxid;
+
+    #[test]
+    fn should_build_response_from_zk34_stat_response_body() {
+        let stat_34_resp_body = fs::read_to_string("../../fixtures/3.4/stat.response").unwrap();
+        let stat_34_resp = ZK4LWStat::build_response(stat_34_resp_body.as_str()).unwrap();
+
+        assert_eq!(stat_34_resp.server.version, ZK4LWVersion::new(3, 4, 14));
+        assert_eq!(stat_34_resp.server.latency.max, 4);
+        assert_eq!(stat_34_resp.server.packets_received, 128);
+        assert_eq!(stat_34_resp.server.packets_sent, 127);
+        assert_eq!(stat_34_resp.server.num_alive_connections, 2);
+        assert_eq!(stat_34_resp.server.zxid, ZK4LWZxid::new(1, 3));
+        assert_eq!(stat_34_resp.server.server_state, LEADER);
+        assert_eq!(stat_34_resp.server.znode_count, 4);
+        assert_eq!(stat_34_resp.server.last_proposal_size.unwrap(), 48);
+        assert_eq!(stat_34_resp.server.misc.len(), 0);
+        assert_eq!(stat_34_resp.clients.len(), 2);
+        assert_eq!(
+            stat_34_resp.clients[1],
+            ZK4LWStatClient {
+                address: "172.18.0.7".parse().unwrap(),
+                port: 41816,
+                interest_ops: 1,
+                queued: 0,
+                received: 120,
+                sent: 121,
+            }
+        );
+    }
+
+    #[test]
+    fn should_build_response_from_zk35_stat_response_body() {
+        let stat_35_resp_body = fs::read_to_string("../../fixtures/3.5/stat.response").unwrap();
+        let stat_35_resp = ZK4LWStat::build_response(stat_35_resp_body.as_str()).unwrap();
+
+        assert_eq!(stat_35_resp.server.version, ZK4LWVersion::new(3, 5, 8));
+        assert_eq!(stat_35_resp.server.zxid, ZK4LWZxid::new(2, 0));
+        assert_eq!(stat_35_resp.server.server_state, LEADER);
+        assert_eq!(stat_35_resp.server.misc.len(), 0);
+        assert_eq!(stat_35_resp.clients.len(), 1);
+        assert_eq!(stat_35_resp.clients[0].port, 60220);
+        assert_eq!(stat_35_resp.clients[0].received, 1);
+        assert_eq!(stat_35_resp.clients[0].sent, 0);
+    }
+
+    #[test]
+    fn should_build_response_from_zk36_stat_response_body() {
+        let stat_36_resp_body = fs::read_to_string("../../fixtures/3.6/stat.response").unwrap();
+        let stat_36_resp = ZK4LWStat::build_response(stat_36_resp_body.as_str()).unwrap();
+
+        assert_eq!(stat_36_resp.server.version, ZK4LWVersion::new(3, 6, 1));
+        assert_eq!(stat_36_resp.server.latency.avg, 0.6667);
+        assert_eq!(stat_36_resp.server.num_alive_connections, 3);
+        assert_eq!(stat_36_resp.server.zxid, ZK4LWZxid::new(3, 4));
+        assert_eq!(stat_36_resp.server.znode_count, 7);
+        assert_eq!(stat_36_resp.server.misc.len(), 0);
+        assert_eq!(stat_36_resp.clients.len(), 3);
+        assert_eq!(
+            stat_36_resp.clients[2].address,
+            "::1".parse::<IpAddr>().unwrap()
+        );
+        assert_eq!(stat_36_resp.clients[2].port, 51402);
+        assert_eq!(
+            stat_36_resp.clients.iter().map(|c| c.received).sum::<i64>(),
+            50
+        );
+    }
+
+    #[test]
+    fn should_skip_bad_clients_if_lenient() {
+        let stat_34_resp_body = fs::read_to_string("../../fixtures/3.4/stat.response")
+            .unwrap()
+            .replace("recved=1,", "recved=many,");
+
+        assert!(ZK4LWStat::build_response(stat_34_resp_body.as_str()).is_err());
+
+        let mut parser = ZK4LWParser::new(ZK4LWParsePolicy::LENIENT);
+        let stat_34_resp =
+            ZK4LWStat::build_response_with_parser(stat_34_resp_body.as_str(), &mut parser).unwrap();
+        assert_eq!(stat_34_resp.clients.len(), 1);
+        assert_eq!(stat_34_resp.clients[0].port, 41816);
+        assert_eq!(stat_34_resp.server.znode_count, 4);
+
+        let warnings = parser.into_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].field.starts_with("/172.18.0.1:59126"));
+    }
+}
@@ -0,0 +1,172 @@
+//! The 4LW Trace Mask commands. Also known as "gtmk" and "stmk".
+//!
+//! These commands read and change the trace mask of the server, that controls
+//! which categories of events are logged at `TRACE` level:
+//!
+//! * `gtmk`: outputs the current trace mask
+//! * `stmk`: sets the trace mask, and outputs the new one
+//!
+//! NOTE: `stmk` changes the state of the server, so it is marked as `ZK4LWCommand::MUTATING`.
+//!
+//! Available since: ZooKeeper 3.3.0
+
+use std::borrow::Cow;
+
+use crate::{client::*, errors::*, result::*};
+
+const COMMAND_GET_TRACE_MASK: &str = "gtmk";
+const COMMAND_SET_TRACE_MASK: &str = "stmk";
+
+bitflags! {
+    /// Trace mask of the server: each flag enables tracing of a category of events
+    ///
+    /// NOTE: Masks with bits that don't correspond to a known category are refused.
+    pub struct ZK4LWTraceMask: i64 {
+        const CLIENT_REQUEST = 1 << 1;
+        const CLIENT_DATA_PACKET = 1 << 2;
+        const CLIENT_PING = 1 << 3;
+        const SERVER_PACKET = 1 << 4;
+        const SESSION = 1 << 5;
+        const EVENT_DELIVERY = 1 << 6;
+        const SERVER_PING = 1 << 7;
+        const WARNING = 1 << 8;
+        const JMX = 1 << 9;
+    }
+}
+
+/// Default trace mask of the server (i.e. `306`)
+impl Default for ZK4LWTraceMask {
+    fn default() -> Self {
+        Self::CLIENT_REQUEST | Self::SERVER_PACKET | Self::SESSION | Self::WARNING
+    }
+}
+
+/// The trace mask is serialized as its integer value, like the server outputs it
+#[cfg(feature = "serde")]
+impl serde::Serialize for ZK4LWTraceMask {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(self.bits())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ZK4LWTraceMask {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bits = i64::deserialize(deserializer)?;
+        Self::from_bits(bits).ok_or_else(|| {
+            serde::de::Error::custom(format!("unknown bits in trace mask: {}", bits))
+        })
+    }
+}
+
+fn trace_mask_from_response(response_body: &str) -> ZK4LWResult<ZK4LWTraceMask> {
+    let bits = response_body.trim().parse()?;
+    ZK4LWTraceMask::from_bits(bits).ok_or_else(|| {
+        ZK4LWError::ParseStringError(format!(
+            "Unknown bits in trace mask: '{}'",
+            response_body.trim()
+        ))
+    })
+}
+
+/// The Get Trace Mask (i.e. "gtmk") command
+#[derive(Default)]
+pub struct ZK4LWGetTraceMask;
+
+impl ZK4LWCommand for ZK4LWGetTraceMask {
+    type Response = ZK4LWTraceMask;
+
+    const NAME: &'static str = COMMAND_GET_TRACE_MASK;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        trace_mask_from_response(response_body)
+    }
+}
+
+/// The Set Trace Mask (i.e. "stmk") command
+///
+/// The mask is sent after the command, as an 8 bytes big-endian integer.
+pub struct ZK4LWSetTraceMask {
+    pub mask: ZK4LWTraceMask,
+}
+
+impl ZK4LWSetTraceMask {
+    pub fn new(mask: ZK4LWTraceMask) -> ZK4LWSetTraceMask {
+        ZK4LWSetTraceMask { mask }
+    }
+}
+
+impl ZK4LWCommand for ZK4LWSetTraceMask {
+    type Response = ZK4LWTraceMask;
+
+    const NAME: &'static str = COMMAND_SET_TRACE_MASK;
+    const MUTATING: bool = true;
+
+    fn request_body(&self) -> Cow<'static, [u8]> {
+        let mut body = Self::NAME.as_bytes().to_vec();
+        body.extend_from_slice(&self.mask.bits().to_be_bytes());
+        Cow::Owned(body)
+    }
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        trace_mask_from_response(response_body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::client::ZK4LWCommand;
+    use crate::commands::tracemask::{ZK4LWGetTraceMask, ZK4LWSetTraceMask, ZK4LWTraceMask};
+    use crate::errors::ZK4LWError;
+
+    #[test]
+    fn should_build_response_from_gtmk_response_bodies() {
+        for version in ["3.4", "3.5", "3.6"].iter() {
+            let gtmk_resp_body =
+                fs::read_to_string(format!("../../fixtures/{}/gtmk.response", version)).unwrap();
+            let gtmk_resp = ZK4LWGetTraceMask::build_response(gtmk_resp_body.as_str()).unwrap();
+            assert_eq!(gtmk_resp, ZK4LWTraceMask::default());
+            assert_eq!(gtmk_resp.bits(), 306);
+        }
+
+        assert!(ZK4LWGetTraceMask::build_response("not a mask").is_err());
+        match ZK4LWGetTraceMask::build_response("307\n") {
+            Err(ZK4LWError::ParseStringError(message)) => assert!(message.contains("307")),
+            _ => panic!("Expected ParseStringError"),
+        }
+    }
+
+    #[test]
+    fn should_build_request_body_with_trace_mask_argument() {
+        let stmk = ZK4LWSetTraceMask::new(ZK4LWTraceMask::CLIENT_PING | ZK4LWTraceMask::JMX);
+        assert_eq!(
+            stmk.request_body().as_ref(),
+            b"stmk\x00\x00\x00\x00\x00\x00\x02\x08"
+        );
+
+        let stmk_resp = ZK4LWSetTraceMask::build_response("520\n").unwrap();
+        assert_eq!(stmk_resp, stmk.mask);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_serialize_and_deserialize_trace_mask_as_integer() {
+        let json = serde_json::to_value(ZK4LWTraceMask::default()).unwrap();
+        assert_eq!(json, 306);
+
+        let mask: ZK4LWTraceMask = serde_json::from_value(json).unwrap();
+        assert_eq!(mask, ZK4LWTraceMask::default());
+
+        assert!(serde_json::from_value::<ZK4LWTraceMask>(serde_json::json!(307)).is_err());
+    }
+
+    #[test]
+    fn should_mark_only_set_trace_mask_as_mutating() {
+        assert_eq!(
+            [ZK4LWSetTraceMask::MUTATING, ZK4LWGetTraceMask::MUTATING],
+            [true, false]
+        );
+    }
+}
@@ -0,0 +1,330 @@
+//! The 4LW Watches commands. Also known as "wchs", "wchc" and "wchp".
+//!
+//! These commands output information about the watches set on the server:
+//!
+//! * `wchs`: brief summary of the watches
+//! * `wchc`: watches by session (i.e. for each session, the paths it's watching)
+//! * `wchp`: watches by path (i.e. for each path, the sessions watching it)
+//!
+//! NOTE: `wchc` and `wchp` can be expensive (i.e. impact server performance), use them carefully.
+//!
+//! Available since: ZooKeeper 3.3.0
+
+use std::collections::BTreeMap;
+
+use crate::{client::*, errors::*, parsing::*, policy::*, result::*};
+
+const COMMAND_SUMMARY: &str = "wchs";
+const COMMAND_BY_SESSION: &str = "wchc";
+const COMMAND_BY_PATH: &str = "wchp";
+
+const SUMMARY_CONNECTIONS_SUFFIX: &str = "connections watching";
+const SUMMARY_PATHS_SUFFIX: &str = "paths";
+const SUMMARY_TOTAL_WATCHES_PREFIX: &str = "Total watches:";
+
+/// Response to the `wchs` command
+#[derive(Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWWatchesSummaryResponse {
+    /// Number of connections with at least a watch set
+    pub connections: i64,
+    /// Number of watched paths
+    pub paths: i64,
+    /// Number of watches set
+    pub total_watches: i64,
+}
+
+/// Response to the `wchc` command
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWWatchesBySessionResponse {
+    /// Watched paths, by session id
+    pub sessions: BTreeMap<u64, Vec<String>>,
+}
+
+/// Response to the `wchp` command
+#[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWWatchesByPathResponse {
+    /// Watching session ids, by path
+    pub paths: BTreeMap<String, Vec<u64>>,
+}
+
+/// The Watches Summary (i.e. "wchs") command
+#[derive(Default)]
+pub struct ZK4LWWatchesSummary;
+
+impl ZK4LWCommand for ZK4LWWatchesSummary {
+    type Response = ZK4LWWatchesSummaryResponse;
+
+    const NAME: &'static str = COMMAND_SUMMARY;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        Self::build_response_with_parser(response_body, &mut ZK4LWParser::default())
+    }
+
+    fn build_response_with_parser(
+        response_body: &str,
+        parser: &mut ZK4LWParser,
+    ) -> ZK4LWResult<Self::Response> {
+        let parse_err = || {
+            ZK4LWError::ParseStringError(format!(
+                "Unable to parse watches summary from string: '{}'",
+                response_body
+            ))
+        };
+
+        let mut response = ZK4LWWatchesSummaryResponse::default();
+        let mut lines = response_body
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty());
+
+        // Parse "<N> connections watching <M> paths"
+        let (connections, paths) = lines
+            .next()
+            .and_then(|l| l.strip_suffix(SUMMARY_PATHS_SUFFIX))
+            .and_then(|l| l.split_once(SUMMARY_CONNECTIONS_SUFFIX))
+            .ok_or_else(parse_err)?;
+        parser.field(
+            "connections",
+            connections
+                .trim()
+                .parse()
+                .map(|c| response.connections = c)
+                .map_err(ZK4LWError::from),
+        )?;
+        parser.field(
+            "paths",
+            paths
+                .trim()
+                .parse()
+                .map(|p| response.paths = p)
+                .map_err(ZK4LWError::from),
+        )?;
+
+        // Parse "Total watches:<N>"
+        let total_watches = lines
+            .next()
+            .and_then(|l| l.strip_prefix(SUMMARY_TOTAL_WATCHES_PREFIX))
+            .ok_or_else(parse_err)?;
+        parser.field(
+            "total_watches",
+            total_watches
+                .trim()
+                .parse()
+                .map(|t| response.total_watches = t)
+                .map_err(ZK4LWError::from),
+        )?;
+
+        Ok(response)
+    }
+}
+
+/// The Watches by Session (i.e. "wchc") command
+#[derive(Default)]
+pub struct ZK4LWWatchesBySession;
+
+impl ZK4LWCommand for ZK4LWWatchesBySession {
+    type Response = ZK4LWWatchesBySessionResponse;
+
+    const NAME: &'static str = COMMAND_BY_SESSION;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        Self::build_response_with_parser(response_body, &mut ZK4LWParser::default())
+    }
+
+    fn build_response_with_parser(
+        response_body: &str,
+        parser: &mut ZK4LWParser,
+    ) -> ZK4LWResult<Self::Response> {
+        let mut response = ZK4LWWatchesBySessionResponse::default();
+
+        // Sessions that fail to parse are skipped, if the parser is lenient
+        for (session_id, paths) in tab_indented_bytes_to_groups(response_body)?.into_iter() {
+            match hex_to_u64(session_id) {
+                Ok(session_id) => {
+                    response
+                        .sessions
+                        .insert(session_id, paths.into_iter().map(String::from).collect());
+                }
+                Err(error) => parser.field(session_id, Err(error))?,
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+/// The Watches by Path (i.e. "wchp") command
+#[derive(Default)]
+pub struct ZK4LWWatchesByPath;
+
+impl ZK4LWCommand for ZK4LWWatchesByPath {
+    type Response = ZK4LWWatchesByPathResponse;
+
+    const NAME: &'static str = COMMAND_BY_PATH;
+
+    fn build_response(response_body: &str) -> ZK4LWResult<Self::Response> {
+        Self::build_response_with_parser(response_body, &mut ZK4LWParser::default())
+    }
+
+    fn build_response_with_parser(
+        response_body: &str,
+        parser: &mut ZK4LWParser,
+    ) -> ZK4LWResult<Self::Response> {
+        let mut response = ZK4LWWatchesByPathResponse::default();
+
+        // Session ids that fail to parse are skipped, if the parser is lenient
+        for (path, session_ids) in tab_indented_bytes_to_groups(response_body)?.into_iter() {
+            let mut parsed_session_ids = Vec::with_capacity(session_ids.len());
+            for session_id in session_ids {
+                match hex_to_u64(session_id) {
+                    Ok(session_id) => parsed_session_ids.push(session_id),
+                    Err(error) => parser.field(path, Err(error))?,
+                }
+            }
+            response.paths.insert(path.into(), parsed_session_ids);
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::client::ZK4LWCommand;
+    use crate::commands::watches::{
+        ZK4LWWatchesByPath, ZK4LWWatchesBySession, ZK4LWWatchesSummary, ZK4LWWatchesSummaryResponse,
+    };
+    use crate::policy::{ZK4LWParsePolicy, ZK4LWParser};
+
+    #[test]
+    fn should_build_response_from_zk34_watches_response_bodies() {
+        let wchs_34_resp_body = fs::read_to_string("../../fixtures/3.4/wchs.response").unwrap();
+        let wchs_34_resp = ZK4LWWatchesSummary::build_response(wchs_34_resp_body.as_str()).unwrap();
+        assert_eq!(
+            wchs_34_resp,
+            ZK4LWWatchesSummaryResponse {
+                connections: 1,
+                paths: 2,
+                total_watches: 2,
+            }
+        );
+
+        let wchc_34_resp_body = fs::read_to_string("../../fixtures/3.4/wchc.response").unwrap();
+        let wchc_34_resp =
+            ZK4LWWatchesBySession::build_response(wchc_34_resp_body.as_str()).unwrap();
+        assert_eq!(wchc_34_resp.sessions.len(), 1);
+        assert_eq!(
+            wchc_34_resp.sessions.get(&0x1e0000c3e670000).unwrap(),
+            &vec!["/brokers/ids".to_string(), "/controller".to_string()]
+        );
+
+        let wchp_34_resp_body = fs::read_to_string("../../fixtures/3.4/wchp.response").unwrap();
+        let wchp_34_resp = ZK4LWWatchesByPath::build_response(wchp_34_resp_body.as_str()).unwrap();
+        assert_eq!(wchp_34_resp.paths.len(), 2);
+        assert_eq!(
+            wchp_34_resp.paths.get("/controller").unwrap(),
+            &vec![0x1e0000c3e670000]
+        );
+    }
+
+    #[test]
+    fn should_build_response_from_zk35_watches_response_bodies() {
+        let wchs_35_resp_body = fs::read_to_string("../../fixtures/3.5/wchs.response").unwrap();
+        let wchs_35_resp = ZK4LWWatchesSummary::build_response(wchs_35_resp_body.as_str()).unwrap();
+        assert_eq!(wchs_35_resp.connections, 2);
+        assert_eq!(wchs_35_resp.paths, 3);
+        assert_eq!(wchs_35_resp.total_watches, 4);
+
+        let wchc_35_resp_body = fs::read_to_string("../../fixtures/3.5/wchc.response").unwrap();
+        let wchc_35_resp =
+            ZK4LWWatchesBySession::build_response(wchc_35_resp_body.as_str()).unwrap();
+        assert_eq!(wchc_35_resp.sessions.len(), 2);
+        assert_eq!(
+            wchc_35_resp.sessions.values().map(Vec::len).sum::<usize>() as i64,
+            wchs_35_resp.total_watches
+        );
+
+        let wchp_35_resp_body = fs::read_to_string("../../fixtures/3.5/wchp.response").unwrap();
+        let wchp_35_resp = ZK4LWWatchesByPath::build_response(wchp_35_resp_body.as_str()).unwrap();
+        assert_eq!(wchp_35_resp.paths.len() as i64, wchs_35_resp.paths);
+        assert_eq!(
+            wchp_35_resp.paths.get("/controller").unwrap(),
+            &vec![0x1e00004b8d90001, 0x1e00004b8d90002]
+        );
+    }
+
+    #[test]
+    fn should_build_response_from_zk36_watches_response_bodies() {
+        let wchs_36_resp_body = fs::read_to_string("../../fixtures/3.6/wchs.response").unwrap();
+        let wchs_36_resp = ZK4LWWatchesSummary::build_response(wchs_36_resp_body.as_str()).unwrap();
+        assert_eq!(wchs_36_resp.connections, 2);
+        assert_eq!(wchs_36_resp.paths, 3);
+        assert_eq!(wchs_36_resp.total_watches, 5);
+
+        let wchc_36_resp_body = fs::read_to_string("../../fixtures/3.6/wchc.response").unwrap();
+        let wchc_36_resp =
+            ZK4LWWatchesBySession::build_response(wchc_36_resp_body.as_str()).unwrap();
+        assert_eq!(
+            wchc_36_resp.sessions.keys().copied().collect::<Vec<u64>>(),
+            vec![0x1e0000a1f2c0000, 0x1e0000a1f2c0001]
+        );
+
+        let wchp_36_resp_body = fs::read_to_string("../../fixtures/3.6/wchp.response").unwrap();
+        let wchp_36_resp = ZK4LWWatchesByPath::build_response(wchp_36_resp_body.as_str()).unwrap();
+        assert_eq!(
+            wchp_36_resp.paths.keys().cloned().collect::<Vec<String>>(),
+            vec!["/brokers/ids", "/config/topics", "/controller"]
+        );
+        assert_eq!(
+            wchp_36_resp.paths.values().map(Vec::len).sum::<usize>() as i64,
+            wchs_36_resp.total_watches
+        );
+    }
+
+    #[test]
+    fn should_fail_to_build_response_from_unexpected_watches_response_bodies() {
+        assert!(ZK4LWWatchesSummary::build_response("").is_err());
+        assert!(ZK4LWWatchesSummary::build_response("1 connections watching 2 paths").is_err());
+        assert!(ZK4LWWatchesBySession::build_response("not-a-session-id\n\t/path\n").is_err());
+        assert!(ZK4LWWatchesByPath::build_response("/path\n\tnot-a-session-id\n").is_err());
+    }
+
+    #[test]
+    fn should_keep_going_on_bad_watches_if_lenient() {
+        let mut parser = ZK4LWParser::new(ZK4LWParsePolicy::LENIENT);
+        let wchs_resp = ZK4LWWatchesSummary::build_response_with_parser(
+            "1 connections watching many paths\nTotal watches:2\n",
+            &mut parser,
+        )
+        .unwrap();
+        assert_eq!(wchs_resp.connections, 1);
+        assert_eq!(wchs_resp.total_watches, 2);
+        assert_eq!(parser.warnings()[0].field, "paths");
+
+        let mut parser = ZK4LWParser::new(ZK4LWParsePolicy::LENIENT);
+        let wchc_resp = ZK4LWWatchesBySession::build_response_with_parser(
+            "not-a-session-id\n\t/path\n0x1\n\t/controller\n",
+            &mut parser,
+        )
+        .unwrap();
+        assert_eq!(
+            wchc_resp.sessions.keys().copied().collect::<Vec<u64>>(),
+            vec![1]
+        );
+        assert_eq!(parser.warnings()[0].field, "not-a-session-id");
+
+        let mut parser = ZK4LWParser::new(ZK4LWParsePolicy::LENIENT);
+        let wchp_resp = ZK4LWWatchesByPath::build_response_with_parser(
+            "/path\n\tnot-a-session-id\n\t0x1\n",
+            &mut parser,
+        )
+        .unwrap();
+        assert_eq!(wchp_resp.paths.get("/path").unwrap(), &vec![1]);
+        assert_eq!(parser.warnings()[0].field, "/path");
+    }
+}
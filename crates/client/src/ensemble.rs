@@ -0,0 +1,400 @@
+//! The Zookeeper "Four Letter Words" commands client for a whole Ensemble.
+//!
+//! It's configured with the same connect string of Zookeeper clients
+//! (e.g. `zk1:2181,zk2:2181,zk3:2181/chroot`), and executes commands against
+//! one, any or all the servers in it.
+
+use std::{collections::HashMap, fmt, net, panic, str, thread};
+
+use rand::seq::SliceRandom;
+
+use crate::{client::*, errors::*, result::*};
+
+const DEFAULT_PORT: u16 = 2181;
+const SERVERS_SEPARATOR: char = ',';
+const CHROOT_PREFIX: char = '/';
+
+/// Address of a server in the Ensemble
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ZK4LWServerAddr {
+    /// Host of the server; can be both an IP or a Hostname
+    pub host: String,
+    pub port: u16,
+}
+
+impl ZK4LWServerAddr {
+    pub fn new<S: Into<String>>(host: S, port: u16) -> Self {
+        ZK4LWServerAddr {
+            host: host.into(),
+            port,
+        }
+    }
+}
+
+impl str::FromStr for ZK4LWServerAddr {
+    type Err = ZK4LWError;
+
+    /// Parses `host[:port]` (e.g. `zk1:2181`, or `[::1]:2181` for IPv6): the port defaults to `2181`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_err = || {
+            ZK4LWError::ParseStringError(format!(
+                "Unable to parse server address from string: '{}'",
+                s
+            ))
+        };
+
+        let s = s.trim();
+        let (host, port) = match s.strip_prefix('[') {
+            // IPv6, between brackets
+            Some(rest) => {
+                let (host, port) = rest.split_once(']').ok_or_else(parse_err)?;
+                match port {
+                    "" => (host, None),
+                    port => (host, Some(port.strip_prefix(':').ok_or_else(parse_err)?)),
+                }
+            }
+            // IPv6, without port
+            None if s.parse::<net::Ipv6Addr>().is_ok() => (s, None),
+            None => match s.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (s, None),
+            },
+        };
+
+        if host.is_empty() {
+            return Err(parse_err());
+        }
+        let port = match port {
+            Some(port) => port.parse().map_err(|_| parse_err())?,
+            None => DEFAULT_PORT,
+        };
+
+        Ok(ZK4LWServerAddr::new(host, port))
+    }
+}
+
+impl fmt::Display for ZK4LWServerAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.host.parse::<net::Ipv6Addr>().is_ok() {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// A Zookeeper connect string (e.g. `zk1:2181,zk2:2181,zk3:2181/chroot`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZK4LWConnectString {
+    pub servers: Vec<ZK4LWServerAddr>,
+    /// Path all the paths of Zookeeper clients are relative to (e.g. `/chroot`), if any
+    ///
+    /// NOTE: 4LW commands are not affected by it (e.g. `wchp` reports absolute paths).
+    pub chroot: Option<String>,
+}
+
+impl str::FromStr for ZK4LWConnectString {
+    type Err = ZK4LWError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (servers, chroot) = match s.find(CHROOT_PREFIX) {
+            Some(chroot_start) => (&s[..chroot_start], Some(s[chroot_start..].trim())),
+            None => (s, None),
+        };
+
+        let servers = servers
+            .split(SERVERS_SEPARATOR)
+            .map(str::trim)
+            .filter(|server| !server.is_empty())
+            .map(str::parse)
+            .collect::<ZK4LWResult<Vec<ZK4LWServerAddr>>>()?;
+        if servers.is_empty() {
+            return Err(ZK4LWError::ParseStringError(format!(
+                "Connect string contains no server: '{}'",
+                s
+            )));
+        }
+
+        // The root is the same as no chroot
+        let chroot = match chroot {
+            Some(chroot) if chroot.len() > 1 && chroot.ends_with(CHROOT_PREFIX) => {
+                return Err(ZK4LWError::ParseStringError(format!(
+                    "Chroot of connect string can't end with '/': '{}'",
+                    s
+                )))
+            }
+            Some(chroot) if chroot.len() > 1 => Some(chroot.to_string()),
+            _ => None,
+        };
+
+        Ok(ZK4LWConnectString { servers, chroot })
+    }
+}
+
+/// The Zookeeper "Four Letter Words" client for a whole Ensemble
+///
+/// Commands are executed by a `ZK4LWClient` for each server,
+/// configured as the builder the Ensemble client was built from (see `ZK4LWClientBuilder::build_ensemble`).
+#[derive(Debug, Clone)]
+pub struct ZK4LWEnsembleClient {
+    connect_string: ZK4LWConnectString,
+    builder: ZK4LWClientBuilder,
+}
+
+impl ZK4LWEnsembleClient {
+    /// Create a new ZK 4LW Ensemble client, without timeouts
+    ///
+    /// # Arguments
+    /// * `connect_string` - the connect string of the Ensemble (e.g. `zk1:2181,zk2:2181,zk3:2181/chroot`)
+    pub fn new(connect_string: &str) -> ZK4LWResult<Self> {
+        ZK4LWClient::builder().build_ensemble(connect_string)
+    }
+
+    pub(crate) fn with_builder(
+        connect_string: &str,
+        builder: ZK4LWClientBuilder,
+    ) -> ZK4LWResult<Self> {
+        Ok(ZK4LWEnsembleClient {
+            connect_string: connect_string.parse()?,
+            builder,
+        })
+    }
+
+    /// Servers in the Ensemble, in the order of the connect string
+    pub fn servers(&self) -> &[ZK4LWServerAddr] {
+        &self.connect_string.servers
+    }
+
+    pub fn chroot(&self) -> Option<&str> {
+        self.connect_string.chroot.as_deref()
+    }
+
+    /// Returns the client that executes commands against the given server
+    pub fn client(&self, server: &ZK4LWServerAddr) -> ZK4LWClient {
+        self.builder
+            .clone()
+            .host(server.host.as_str())
+            .port(server.port)
+            .build()
+    }
+
+    /// Execute the given command type against the given server (usually one of `servers`)
+    pub fn execute_on<C: ZK4LWCommand + Default>(
+        &self,
+        server: &ZK4LWServerAddr,
+    ) -> ZK4LWResult<C::Response> {
+        self.execute_command_on(server, &C::default())
+    }
+
+    /// Execute the given command against the given server (usually one of `servers`)
+    pub fn execute_command_on<C: ZK4LWCommand>(
+        &self,
+        server: &ZK4LWServerAddr,
+        command: &C,
+    ) -> ZK4LWResult<C::Response> {
+        self.client(server).execute_command(command)
+    }
+
+    /// Execute the given command type against any server, and return the first response
+    /// together with the server that produced it
+    ///
+    /// See `execute_command_any`.
+    pub fn execute_any<C: ZK4LWCommand + Default>(
+        &self,
+    ) -> ZK4LWResult<(ZK4LWServerAddr, C::Response)> {
+        self.execute_command_any(&C::default())
+    }
+
+    /// Execute the given command against any server, and return the first response
+    /// together with the server that produced it
+    ///
+    /// Servers are tried in random order (to spread the load, like Zookeeper clients do),
+    /// until one succeeds: if none does, the error of the last one is returned.
+    pub fn execute_command_any<C: ZK4LWCommand>(
+        &self,
+        command: &C,
+    ) -> ZK4LWResult<(ZK4LWServerAddr, C::Response)> {
+        let mut servers = self.servers().to_vec();
+        servers.shuffle(&mut rand::thread_rng());
+
+        let mut last_err = None;
+        for server in servers {
+            match self.execute_command_on(&server, command) {
+                Ok(response) => return Ok((server, response)),
+                Err(e) => last_err = Some(e),
+            }
+        }
+
+        // The connect string always contains at least a server
+        Err(last_err.expect("Ensemble without servers"))
+    }
+
+    /// Execute the given command type against all the servers, and return the result of each
+    ///
+    /// See `execute_command_all`.
+    pub fn execute_all<C>(&self) -> HashMap<ZK4LWServerAddr, ZK4LWResult<C::Response>>
+    where
+        C: ZK4LWCommand + Default + Sync,
+        C::Response: Send,
+    {
+        self.execute_command_all(&C::default())
+    }
+
+    /// Execute the given command against all the servers, and return the result of each
+    ///
+    /// The command is executed against every server concurrently, so that one slow server
+    /// doesn't delay the others: set timeouts on the builder, to bound the slowest.
+    pub fn execute_command_all<C>(
+        &self,
+        command: &C,
+    ) -> HashMap<ZK4LWServerAddr, ZK4LWResult<C::Response>>
+    where
+        C: ZK4LWCommand + Sync,
+        C::Response: Send,
+    {
+        thread::scope(|scope| {
+            let executions: Vec<_> = self
+                .servers()
+                .iter()
+                .map(|server| {
+                    let execution = scope.spawn(move || self.execute_command_on(server, command));
+                    (server, execution)
+                })
+                .collect();
+
+            executions
+                .into_iter()
+                .map(|(server, execution)| {
+                    let result = execution.join().unwrap_or_else(|e| panic::resume_unwind(e));
+                    (server.clone(), result)
+                })
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net, thread,
+        time::Duration,
+    };
+
+    use crate::client::ZK4LWClient;
+    use crate::commands::ruok::{ZK4LWRuok, ZK4LWRuokResponse};
+    use crate::ensemble::{ZK4LWConnectString, ZK4LWServerAddr};
+    use crate::errors::ZK4LWError;
+
+    /// Spawns a server that answers "ruok" to the given number of requests
+    fn serve_imok(requests: usize) -> (u16, thread::JoinHandle<()>) {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            for _ in 0..requests {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0u8; 4];
+                stream.read_exact(&mut request).unwrap();
+                stream.write_all(b"imok").unwrap();
+            }
+        });
+
+        (port, server)
+    }
+
+    /// Binds a server that never accepts connections, so that requests to it time out
+    ///
+    /// NOTE: keep the listener alive for the whole test, so that its port can't be reused meanwhile.
+    fn serve_nothing() -> (u16, net::TcpListener) {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        (listener.local_addr().unwrap().port(), listener)
+    }
+
+    #[test]
+    fn should_parse_connect_string() {
+        let connect_string: ZK4LWConnectString =
+            "zk10:2181, zk20:2182,zk30/chroot/app".parse().unwrap();
+        assert_eq!(
+            connect_string.servers,
+            vec![
+                ZK4LWServerAddr::new("zk10", 2181),
+                ZK4LWServerAddr::new("zk20", 2182),
+                ZK4LWServerAddr::new("zk30", 2181),
+            ]
+        );
+        assert_eq!(connect_string.chroot.as_deref(), Some("/chroot/app"));
+
+        let connect_string: ZK4LWConnectString = "10.0.0.1,[::1]:2182,::1/".parse().unwrap();
+        assert_eq!(
+            connect_string.servers,
+            vec![
+                ZK4LWServerAddr::new("10.0.0.1", 2181),
+                ZK4LWServerAddr::new("::1", 2182),
+                ZK4LWServerAddr::new("::1", 2181),
+            ]
+        );
+        assert_eq!(connect_string.chroot, None);
+        assert_eq!(connect_string.servers[1].to_string(), "[::1]:2182");
+
+        for invalid in ["", "/chroot", "zk10:port", "zk10:2181/chroot/", ":2181"].iter() {
+            match invalid.parse::<ZK4LWConnectString>() {
+                Err(ZK4LWError::ParseStringError(_)) => {}
+                _ => panic!("Expected ParseStringError for '{}'", invalid),
+            }
+        }
+    }
+
+    #[test]
+    fn should_execute_commands_against_the_ensemble() {
+        let (port_1, server_1) = serve_imok(3);
+        let (port_2, server_2) = serve_imok(1);
+        let (port_3, _listener_3) = serve_nothing();
+
+        let ensemble = ZK4LWClient::builder()
+            .read_timeout(Duration::from_millis(200))
+            .build_ensemble(&format!(
+                "127.0.0.1:{},127.0.0.1:{},127.0.0.1:{}/chroot",
+                port_1, port_2, port_3
+            ))
+            .unwrap();
+        assert_eq!(ensemble.servers().len(), 3);
+        assert_eq!(ensemble.chroot(), Some("/chroot"));
+
+        // All, with partial failure
+        let results = ensemble.execute_all::<ZK4LWRuok>();
+        assert_eq!(results.len(), 3);
+        let server_3 = ZK4LWServerAddr::new("127.0.0.1", port_3);
+        for (server, result) in results {
+            match result {
+                Ok(response) => assert_eq!(response, ZK4LWRuokResponse::IMOK),
+                Err(ZK4LWError::Timeout(_)) => assert_eq!(server, server_3),
+                Err(e) => panic!("Unexpected error: {}", e),
+            }
+        }
+        server_2.join().unwrap();
+
+        // One
+        let server_1_addr = ZK4LWServerAddr::new("127.0.0.1", port_1);
+        assert!(ensemble
+            .execute_on::<ZK4LWRuok>(&server_1_addr)
+            .unwrap()
+            .is_ok());
+
+        // Any, skipping the servers that fail
+        let (server, response) = ensemble.execute_any::<ZK4LWRuok>().unwrap();
+        assert_eq!(server, server_1_addr);
+        assert!(response.is_ok());
+        server_1.join().unwrap();
+
+        // Any, when all fail
+        let ensemble = ZK4LWClient::builder()
+            .read_timeout(Duration::from_millis(200))
+            .build_ensemble(&format!("127.0.0.1:{}", port_3))
+            .unwrap();
+        match ensemble.execute_any::<ZK4LWRuok>() {
+            Err(ZK4LWError::Timeout(_)) => {}
+            _ => panic!("Expected Timeout"),
+        }
+    }
+}
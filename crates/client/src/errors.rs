@@ -1,6 +1,11 @@
 //! Possible errors that can happen when executing a 4LW command.
 
-use std::{io, num, str};
+// NOTE: the `Fail` derive macro places its `impl` blocks inside an anonymous `const`
+#![allow(non_local_definitions)]
+
+use std::{fmt, io, num, str};
+
+use crate::state::ZK4LWServerState;
 
 /// Possible errors returned by executing `ZK4LWCommand`s
 #[non_exhaustive]
@@ -18,6 +23,38 @@ pub enum ZK4LWError {
     #[fail(display = "Field missing from response: {}", _0)]
     MissingFieldError(&'static str),
 
+    /// The state of the server is set when it was checked before sending the command
+    /// (see `ZK4LWClient::execute_leader_only`), and not just inferred from the response
+    #[fail(
+        display = "Command '{}' can only be executed against the leader (or a standalone server)",
+        _0
+    )]
+    NotLeaderError(&'static str, Option<ZK4LWServerState>),
+
+    #[fail(
+        display = "Command '{}' is not in the whitelist of the server (i.e. `4lw.commands.whitelist`)",
+        _0
+    )]
+    NotWhitelistedError(&'static str),
+
+    #[fail(display = "Server is not currently serving requests")]
+    NotServingError,
+
+    #[fail(display = "Unexpected response: {}", _0)]
+    UnexpectedResponseError(String),
+
+    #[fail(display = "Timed out while executing command, during phase: {}", _0)]
+    Timeout(ZK4LWPhase),
+
+    #[fail(display = "Transport not supported: {}", _0)]
+    UnsupportedTransportError(&'static str),
+
+    #[fail(display = "Proxy failed to tunnel the connection: {}", _0)]
+    ProxyError(String),
+
+    #[fail(display = "Encountered TLS error: {}", _0)]
+    TlsError(String),
+
     #[fail(display = "Encountered I/O error: {}", _0)]
     IoError(#[cause] io::Error),
 
@@ -25,6 +62,27 @@ pub enum ZK4LWError {
     Utf8Error(#[cause] str::Utf8Error),
 }
 
+/// Phase of the execution of a command, as recorded by `ZK4LWError::Timeout`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZK4LWPhase {
+    /// Connecting to the server
+    CONNECT,
+    /// Sending the command
+    WRITE,
+    /// Receiving the response
+    READ,
+}
+
+impl fmt::Display for ZK4LWPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ZK4LWPhase::CONNECT => "connect",
+            ZK4LWPhase::WRITE => "write",
+            ZK4LWPhase::READ => "read",
+        })
+    }
+}
+
 impl From<num::ParseIntError> for ZK4LWError {
     fn from(val: num::ParseIntError) -> Self {
         Self::ParseIntError(val)
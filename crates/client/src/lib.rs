@@ -1,9 +1,20 @@
 #[macro_use]
+extern crate bitflags;
+#[macro_use]
 extern crate failure;
 
+#[cfg(feature = "async")]
+pub mod async_client;
+pub mod capabilities;
 pub mod client;
+pub mod ensemble;
 pub mod errors;
 pub mod result;
+pub mod retry;
 pub mod commands;
 pub mod parsing;
+pub mod policy;
 pub mod state;
+pub mod transport;
+pub mod version;
+pub mod zxid;
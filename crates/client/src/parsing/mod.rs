@@ -1,10 +1,48 @@
-use std::{collections::HashMap, str};
+use std::{
+    collections::{HashMap, HashSet},
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+    str,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
-use crate::result::ZK4LWResult;
+use crate::{
+    commands::common::{ZK4LWSummaryMetrics, ZK4LWTimeMetricSample},
+    errors::ZK4LWError,
+    policy::ZK4LWParser,
+    result::ZK4LWResult,
+};
 
-const LINE_SEPARATOR: &'static str = "\n";
-const KEY_VAL_EQUAL_SEPARATOR: &'static str = "=";
-const KEY_VAL_TAB_SEPARATOR: &'static str = "\t";
+const LINE_SEPARATOR: &str = "\n";
+const KEY_VAL_EQUAL_SEPARATOR: &str = "=";
+const KEY_VAL_TAB_SEPARATOR: &str = "\t";
+const KEY_VAL_COLON_SEPARATOR: &str = ":";
+const HEX_PREFIX: &str = "0x";
+const TRIPLET_SEPARATOR: char = '/';
+const CONNECTION_ADDRESS_PREFIX: char = '/';
+const CONNECTION_PORT_SEPARATOR: char = ':';
+const CONNECTION_INTEREST_OPS_DELIMITERS: (char, char) = ('[', ']');
+const CONNECTION_STATS_DELIMITERS: (char, char) = ('(', ')');
+const CONNECTION_STATS_SEPARATOR: char = ',';
+const CONNECTION_STAT_KEY_VAL_SEPARATOR: char = '=';
+const JAVA_DATE_MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+const JAVA_DATE_UTC_ZONES: [&str; 2] = ["UTC", "GMT"];
+const BUILD_DATE_SEPARATOR: char = '/';
+const PATH_LIST_SEPARATOR: char = ':';
+const MEMORY_SIZE_UNITS: [(&str, i64); 4] = [
+    ("GB", 1024 * 1024 * 1024),
+    ("MB", 1024 * 1024),
+    ("KB", 1024),
+    ("B", 1),
+];
+const SUMMARY_METRIC_PREFIX: &str = "zk_";
+const SUMMARY_METRIC_SEPARATOR: char = '_';
+const SUMMARY_METRIC_AVG_STAT: &str = "avg";
+const SUMMARY_METRIC_STATS: [&str; 9] = [
+    "avg", "min", "max", "cnt", "sum", "p50", "p95", "p99", "p999",
+];
 
 fn bytes_to_key_value<'a>(
     input_utf8: &'a str,
@@ -12,10 +50,10 @@ fn bytes_to_key_value<'a>(
 ) -> ZK4LWResult<HashMap<&'a str, &'a str>> {
     Ok(input_utf8
         .split_terminator(LINE_SEPARATOR)
-        .map(|line| line.split_terminator(separator))
+        .map(|line| line.splitn(2, separator)) //< Values can contain the separator too
         .map(|mut key_val_seq| (key_val_seq.next(), key_val_seq.next()))
         .filter(|(k, v)| k.is_some() && v.is_some()) //< Skip lines that don't split by given separator
-        .map(|(k, v)| (k.unwrap().trim().into(), v.unwrap().trim().into()))
+        .map(|(k, v)| (k.unwrap().trim(), v.unwrap().trim()))
         .collect::<HashMap<&str, &str>>())
 }
 
@@ -29,11 +67,340 @@ pub fn equal_separated_bytes_to_key_value(input_utf8: &str) -> ZK4LWResult<HashM
     bytes_to_key_value(input_utf8, KEY_VAL_EQUAL_SEPARATOR)
 }
 
+/// Parses multi-line `&str` made of key/value strings separated by colon (`:`), into a `HashMap`
+pub fn colon_separated_bytes_to_key_value(input_utf8: &str) -> ZK4LWResult<HashMap<&str, &str>> {
+    bytes_to_key_value(input_utf8, KEY_VAL_COLON_SEPARATOR)
+}
+
+/// Parses an hexadecimal number, with or without `0x` prefix (e.g. a Zxid), into a `u64`
+pub fn hex_to_u64(input_utf8: &str) -> ZK4LWResult<u64> {
+    let input = input_utf8.trim();
+    let digits = input.strip_prefix(HEX_PREFIX).unwrap_or(input);
+
+    Ok(u64::from_str_radix(digits, 16)?)
+}
+
+/// Parses multi-line `&str` made of header lines, each followed by its tab-indented (`\t`) lines,
+/// into a `Vec` of `(header, indented lines)`
+///
+/// Empty lines are skipped; an indented line without a preceding header results in an error.
+pub fn tab_indented_bytes_to_groups(input_utf8: &str) -> ZK4LWResult<Vec<(&str, Vec<&str>)>> {
+    let mut groups: Vec<(&str, Vec<&str>)> = Vec::new();
+
+    for line in input_utf8.split_terminator(LINE_SEPARATOR) {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if line.starts_with(KEY_VAL_TAB_SEPARATOR) {
+            groups
+                .last_mut()
+                .ok_or_else(|| {
+                    ZK4LWError::ParseStringError(format!(
+                        "Unable to parse indented line without header: '{}'",
+                        line
+                    ))
+                })?
+                .1
+                .push(line.trim());
+        } else {
+            groups.push((line.trim(), Vec::new()));
+        }
+    }
+
+    Ok(groups)
+}
+
+/// Parses a (possibly fractional) number of milliseconds, into a `Duration`
+pub fn millis_to_duration(input_utf8: &str) -> ZK4LWResult<Duration> {
+    let millis: f64 = input_utf8.trim().parse()?;
+    if !millis.is_finite() || millis < 0.0 {
+        return Err(ZK4LWError::ParseStringError(format!(
+            "Unable to parse duration from string: '{}'",
+            input_utf8
+        )));
+    }
+
+    Ok(Duration::from_secs_f64(millis / 1000.0))
+}
+
+/// Parses a number of milliseconds since the Unix Epoch, into a `SystemTime`
+pub fn millis_to_system_time(input_utf8: &str) -> ZK4LWResult<SystemTime> {
+    Ok(UNIX_EPOCH + Duration::from_millis(input_utf8.trim().parse()?))
+}
+
+/// Parses a date in the format of Java's `Date::toString()` (e.g. `Thu Aug 06 16:29:40 UTC 2020`), into a `SystemTime`
+///
+/// NOTE: Only dates in the UTC (or GMT) time zone can be parsed, as resolving
+/// any other time zone abbreviation would require a time zone database.
+pub fn java_date_to_system_time(input_utf8: &str) -> ZK4LWResult<SystemTime> {
+    let parse_err = || {
+        ZK4LWError::ParseStringError(format!(
+            "Unable to parse date from string: '{}'",
+            input_utf8
+        ))
+    };
+
+    // Format is "EEE MMM dd HH:mm:ss zzz yyyy"
+    let parts: Vec<&str> = input_utf8.split_whitespace().collect();
+    if parts.len() != 6 || !JAVA_DATE_UTC_ZONES.contains(&parts[4]) {
+        return Err(parse_err());
+    }
+    let month = JAVA_DATE_MONTHS
+        .iter()
+        .position(|m| *m == parts[1])
+        .ok_or_else(parse_err)? as i64
+        + 1;
+    let day: i64 = parts[2].parse()?;
+    let year: i64 = parts[5].parse()?;
+    let time: Vec<i64> = parts[3]
+        .split(':')
+        .map(str::parse)
+        .collect::<Result<Vec<i64>, _>>()?;
+    if time.len() != 3 || !(1..=31).contains(&day) {
+        return Err(parse_err());
+    }
+
+    date_time_to_system_time(year, month, day, (time[0], time[1], time[2])).ok_or_else(parse_err)
+}
+
+/// Parses a build date, as reported by ZooKeeper (e.g. `05/04/2020 15:07 GMT`), into a `SystemTime`
+///
+/// NOTE: Only dates in the UTC (or GMT) time zone can be parsed, as resolving
+/// any other time zone abbreviation would require a time zone database.
+pub fn build_date_to_system_time(input_utf8: &str) -> ZK4LWResult<SystemTime> {
+    let parse_err = || {
+        ZK4LWError::ParseStringError(format!(
+            "Unable to parse build date from string: '{}'",
+            input_utf8
+        ))
+    };
+
+    // Format is "MM/dd/yyyy HH:mm zzz"
+    let parts: Vec<&str> = input_utf8.split_whitespace().collect();
+    if parts.len() != 3 || !JAVA_DATE_UTC_ZONES.contains(&parts[2]) {
+        return Err(parse_err());
+    }
+    let date: Vec<i64> = parts[0]
+        .split(BUILD_DATE_SEPARATOR)
+        .map(str::parse)
+        .collect::<Result<Vec<i64>, _>>()?;
+    let time: Vec<i64> = parts[1]
+        .split(':')
+        .map(str::parse)
+        .collect::<Result<Vec<i64>, _>>()?;
+    if date.len() != 3
+        || time.len() != 2
+        || !(1..=12).contains(&date[0])
+        || !(1..=31).contains(&date[1])
+    {
+        return Err(parse_err());
+    }
+
+    date_time_to_system_time(date[2], date[0], date[1], (time[0], time[1], 0)).ok_or_else(parse_err)
+}
+
+/// Converts a date and time (UTC) in the (proleptic) Gregorian calendar, into a `SystemTime`
+fn date_time_to_system_time(
+    year: i64,
+    month: i64,
+    day: i64,
+    (hours, minutes, seconds): (i64, i64, i64),
+) -> Option<SystemTime> {
+    // Days since Unix Epoch
+    // See: http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let (y, m) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * m + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let secs = days * 86_400 + hours * 3_600 + minutes * 60 + seconds;
+    if secs < 0 {
+        return None;
+    }
+
+    Some(UNIX_EPOCH + Duration::from_secs(secs as u64))
+}
+
+/// Parses a triplet of values separated by slash (`/`), like `min/avg/max`, into a tuple
+pub fn slash_separated_triplet(input_utf8: &str) -> ZK4LWResult<(&str, &str, &str)> {
+    let triplet: Vec<&str> = input_utf8.trim().split(TRIPLET_SEPARATOR).collect();
+    if triplet.len() != 3 {
+        return Err(ZK4LWError::ParseStringError(format!(
+            "Unable to parse triplet from string: '{}'",
+            input_utf8
+        )));
+    }
+
+    Ok((triplet[0], triplet[1], triplet[2]))
+}
+
+/// Parses a line describing a client connection, into its `(address, interest ops, stats)` parts
+///
+/// The input is expected to look like `/172.18.0.1:59126[1](queued=0,recved=120,sent=120)`,
+/// as reported by the `stat` and `cons` commands. The address can be prefixed by the hostname
+/// (i.e. `hostname/172.18.0.1:59126`) and IPv6 addresses are reported without square brackets.
+pub fn connection_line_to_parts(
+    input_utf8: &str,
+) -> ZK4LWResult<(SocketAddr, i64, HashMap<&str, &str>)> {
+    let parse_err = || {
+        ZK4LWError::ParseStringError(format!(
+            "Unable to parse connection from string: '{}'",
+            input_utf8
+        ))
+    };
+
+    let input = input_utf8.trim();
+    let (address, rest) = input
+        .split_once(CONNECTION_INTEREST_OPS_DELIMITERS.0)
+        .ok_or_else(parse_err)?;
+    let (interest_ops, rest) = rest
+        .split_once(CONNECTION_INTEREST_OPS_DELIMITERS.1)
+        .ok_or_else(parse_err)?;
+    let stats = rest
+        .trim()
+        .strip_prefix(CONNECTION_STATS_DELIMITERS.0)
+        .and_then(|s| s.strip_suffix(CONNECTION_STATS_DELIMITERS.1))
+        .ok_or_else(parse_err)?;
+
+    // Drop the (optional) hostname, then split IP and port
+    let address = address
+        .rsplit(CONNECTION_ADDRESS_PREFIX)
+        .next()
+        .ok_or_else(parse_err)?;
+    let (ip, port) = address
+        .rsplit_once(CONNECTION_PORT_SEPARATOR)
+        .ok_or_else(parse_err)?;
+    let ip: IpAddr = ip.parse().map_err(|_| parse_err())?;
+    let address = SocketAddr::new(ip, port.parse().map_err(|_| parse_err())?);
+
+    let stats = stats
+        .split(CONNECTION_STATS_SEPARATOR)
+        .filter_map(|stat| stat.split_once(CONNECTION_STAT_KEY_VAL_SEPARATOR))
+        .map(|(k, v)| (k.trim(), v.trim()))
+        .collect();
+
+    Ok((address, interest_ops.parse()?, stats))
+}
+
+/// Parses a list of paths separated by colon (`:`), like a Java class path, into a `Vec` of `PathBuf`
+pub fn path_list_to_paths(input_utf8: &str) -> Vec<PathBuf> {
+    input_utf8
+        .split(PATH_LIST_SEPARATOR)
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Parses a memory size with unit suffix (e.g. `36MB`), into a number of bytes
+///
+/// Units are powers of 1024, the same way the JVM reports them.
+pub fn memory_size_to_bytes(input_utf8: &str) -> ZK4LWResult<i64> {
+    let input = input_utf8.trim();
+    for (unit, multiplier) in MEMORY_SIZE_UNITS.iter() {
+        if let Some(size) = input.strip_suffix(unit) {
+            return Ok(size.trim().parse::<i64>()? * multiplier);
+        }
+    }
+
+    Ok(input.parse()?)
+}
+
+/// Splits the key of a "summary" metric (e.g. `zk_avg_fsynctime`) into statistic and name
+fn summary_metric_key_to_parts(key: &str) -> Option<(&str, &str)> {
+    let (stat, name) = key
+        .strip_prefix(SUMMARY_METRIC_PREFIX)?
+        .split_once(SUMMARY_METRIC_SEPARATOR)?;
+    if SUMMARY_METRIC_STATS.contains(&stat) {
+        Some((stat, name))
+    } else {
+        None
+    }
+}
+
+/// Parses the value of a statistic of a "summary" metric, into the given sample
+fn summary_metric_stat_to_sample(
+    sample: &mut ZK4LWTimeMetricSample,
+    stat: &str,
+    val: &str,
+) -> ZK4LWResult<()> {
+    match stat {
+        "avg" => sample.avg = val.parse()?,
+        "min" => sample.min = val.parse()?,
+        "max" => sample.max = val.parse()?,
+        "cnt" => sample.count = Some(val.parse()?),
+        "sum" => sample.sum = Some(val.parse()?),
+        "p50" => sample.p50 = Some(val.parse()?),
+        "p95" => sample.p95 = Some(val.parse()?),
+        "p99" => sample.p99 = Some(val.parse()?),
+        _ => sample.p999 = Some(val.parse()?),
+    }
+
+    Ok(())
+}
+
+/// Reassembles the "summary" metrics in a key/value `HashMap`, into `ZK4LWSummaryMetrics`
+///
+/// A summary metric is recognised by its `zk_avg_<name>` key: all the `zk_<stat>_<name>` keys
+/// of the same family are consumed, while the other key/value pairs are returned untouched.
+/// Statistics that fail to parse are handled by the given parser: if it's lenient,
+/// they keep their default value.
+pub fn key_value_to_summary_metrics<'a>(
+    key_values: HashMap<&'a str, &'a str>,
+    parser: &mut ZK4LWParser,
+) -> ZK4LWResult<(ZK4LWSummaryMetrics, HashMap<&'a str, &'a str>)> {
+    // NOTE: Keys like `zk_max_file_descriptor_count` look like a statistic,
+    // but don't belong to a family: that's why we only trust the average
+    let names: HashSet<&str> = key_values
+        .keys()
+        .filter_map(|key| summary_metric_key_to_parts(key))
+        .filter(|(stat, _)| *stat == SUMMARY_METRIC_AVG_STAT)
+        .map(|(_, name)| name)
+        .collect();
+
+    let mut families: HashMap<&str, ZK4LWTimeMetricSample> = HashMap::new();
+    let mut remaining = HashMap::new();
+    for (key, val) in key_values.into_iter() {
+        let (stat, name) = match summary_metric_key_to_parts(key) {
+            Some((stat, name)) if names.contains(name) => (stat, name),
+            _ => {
+                remaining.insert(key, val);
+                continue;
+            }
+        };
+
+        let sample = families.entry(name).or_default();
+        parser.field(key, summary_metric_stat_to_sample(sample, stat, val))?;
+    }
+
+    let mut summaries = ZK4LWSummaryMetrics::default();
+    for (name, sample) in families.into_iter() {
+        if sample.has_percentiles() {
+            summaries.time_samples.insert(name.into(), sample);
+        } else {
+            summaries.samples.insert(name.into(), sample.into());
+        }
+    }
+
+    Ok((summaries, remaining))
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs;
+    use std::{
+        fs,
+        time::{Duration, UNIX_EPOCH},
+    };
 
-    use crate::parsing::{equal_separated_bytes_to_key_value, tab_separated_bytes_to_key_value};
+    use crate::parsing::*;
+    use crate::policy::ZK4LWParser;
 
     #[test]
     fn should_parse_tab_separated_bytes_to_key_value() {
@@ -79,4 +446,187 @@ mod tests {
         let envi_3_6_map = equal_separated_bytes_to_key_value(&envi_3_6_resp).unwrap();
         assert_eq!(18, envi_3_6_map.len());
     }
+
+    #[test]
+    fn should_parse_colon_separated_bytes_to_key_value() {
+        // Parse 'srvr' responses
+        let srvr_3_4_resp = fs::read_to_string("../../fixtures/3.4/srvr.response").unwrap();
+        let srvr_3_4_map = colon_separated_bytes_to_key_value(&srvr_3_4_resp).unwrap();
+        assert_eq!(10, srvr_3_4_map.len());
+        assert_eq!(
+            "3.4.14-4c25d480e66aadd371de8bd2fd8da255ac140bcf, built on 03/06/2019 16:18 GMT",
+            *srvr_3_4_map.get("Zookeeper version").unwrap()
+        );
+
+        let srvr_3_5_resp = fs::read_to_string("../../fixtures/3.5/srvr.response").unwrap();
+        let srvr_3_5_map = colon_separated_bytes_to_key_value(&srvr_3_5_resp).unwrap();
+        assert_eq!(10, srvr_3_5_map.len());
+
+        let srvr_3_6_resp = fs::read_to_string("../../fixtures/3.6/srvr.response").unwrap();
+        let srvr_3_6_map = colon_separated_bytes_to_key_value(&srvr_3_6_resp).unwrap();
+        assert_eq!(10, srvr_3_6_map.len());
+    }
+
+    #[test]
+    fn should_parse_hex_to_u64() {
+        assert_eq!(hex_to_u64("0x100000000").unwrap(), 0x1_0000_0000);
+        assert_eq!(hex_to_u64("1a").unwrap(), 26);
+        assert_eq!(hex_to_u64("0x0").unwrap(), 0);
+        assert!(hex_to_u64("0xzz").is_err());
+    }
+
+    #[test]
+    fn should_parse_tab_indented_bytes_to_groups() {
+        // Parse 'wchc' responses
+        let wchc_3_6_resp = fs::read_to_string("../../fixtures/3.6/wchc.response").unwrap();
+        let wchc_3_6_groups = tab_indented_bytes_to_groups(&wchc_3_6_resp).unwrap();
+        assert_eq!(2, wchc_3_6_groups.len());
+        assert_eq!(
+            ("0x1e0000a1f2c0001", vec!["/controller", "/brokers/ids"]),
+            wchc_3_6_groups[1]
+        );
+
+        // Parse 'wchp' responses
+        let wchp_3_6_resp = fs::read_to_string("../../fixtures/3.6/wchp.response").unwrap();
+        let wchp_3_6_groups = tab_indented_bytes_to_groups(&wchp_3_6_resp).unwrap();
+        assert_eq!(3, wchp_3_6_groups.len());
+
+        assert!(tab_indented_bytes_to_groups("\t/orphan\n").is_err());
+    }
+
+    #[test]
+    fn should_parse_millis_to_duration() {
+        assert_eq!(
+            millis_to_duration("30000").unwrap(),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            millis_to_duration("0.5").unwrap(),
+            Duration::from_micros(500)
+        );
+        assert!(millis_to_duration("-1").is_err());
+        assert!(millis_to_duration("NA").is_err());
+    }
+
+    #[test]
+    fn should_parse_millis_to_system_time() {
+        assert_eq!(
+            millis_to_system_time("1596729380811").unwrap(),
+            UNIX_EPOCH + Duration::from_millis(1_596_729_380_811)
+        );
+        assert!(millis_to_system_time("-1").is_err());
+    }
+
+    #[test]
+    fn should_parse_java_date_to_system_time() {
+        assert_eq!(
+            java_date_to_system_time("Thu Aug 06 16:29:40 UTC 2020").unwrap(),
+            UNIX_EPOCH + Duration::from_secs(1_596_731_380)
+        );
+        assert_eq!(
+            java_date_to_system_time("Thu Jan 01 00:00:00 GMT 1970").unwrap(),
+            UNIX_EPOCH
+        );
+        assert_eq!(
+            java_date_to_system_time("Thu Feb 29 23:59:59 UTC 2024").unwrap(),
+            UNIX_EPOCH + Duration::from_secs(1_709_251_199)
+        );
+        assert!(java_date_to_system_time("Thu Aug 06 16:29:40 CEST 2020").is_err());
+        assert!(java_date_to_system_time("Thu Foo 06 16:29:40 UTC 2020").is_err());
+        assert!(java_date_to_system_time("Thu Aug 06 16:29 UTC 2020").is_err());
+    }
+
+    #[test]
+    fn should_parse_build_date_to_system_time() {
+        assert_eq!(
+            build_date_to_system_time("03/06/2019 16:18 GMT").unwrap(),
+            UNIX_EPOCH + Duration::from_secs(1_551_889_080)
+        );
+        assert_eq!(
+            build_date_to_system_time("05/04/2020 15:07 UTC").unwrap(),
+            UNIX_EPOCH + Duration::from_secs(1_588_604_820)
+        );
+        assert!(build_date_to_system_time("03/06/2019 16:18 PST").is_err());
+        assert!(build_date_to_system_time("13/06/2019 16:18 GMT").is_err());
+        assert!(build_date_to_system_time("03/06/2019 GMT").is_err());
+    }
+
+    #[test]
+    fn should_parse_slash_separated_triplet() {
+        assert_eq!(
+            slash_separated_triplet("0/0.0/5").unwrap(),
+            ("0", "0.0", "5")
+        );
+        assert_eq!(
+            slash_separated_triplet("-1/-1/-1").unwrap(),
+            ("-1", "-1", "-1")
+        );
+        assert!(slash_separated_triplet("0/0").is_err());
+    }
+
+    #[test]
+    fn should_parse_connection_line_to_parts() {
+        let (address, interest_ops, stats) =
+            connection_line_to_parts(" /172.18.0.7:41816[1](queued=0,recved=120,sent=121)")
+                .unwrap();
+        assert_eq!(address, "172.18.0.7:41816".parse().unwrap());
+        assert_eq!(interest_ops, 1);
+        assert_eq!(stats.len(), 3);
+        assert_eq!(*stats.get("queued").unwrap(), "0");
+        assert_eq!(*stats.get("recved").unwrap(), "120");
+        assert_eq!(*stats.get("sent").unwrap(), "121");
+
+        let (address, interest_ops, stats) =
+            connection_line_to_parts("localhost/0:0:0:0:0:0:0:1:51402[0](queued=0)").unwrap();
+        assert_eq!(address, "[::1]:51402".parse().unwrap());
+        assert_eq!(interest_ops, 0);
+        assert_eq!(stats.len(), 1);
+
+        assert!(connection_line_to_parts("/172.18.0.7:41816").is_err());
+        assert!(connection_line_to_parts("/172.18.0.7[1](queued=0)").is_err());
+        assert!(connection_line_to_parts("/not-an-ip:41816[1](queued=0)").is_err());
+    }
+
+    #[test]
+    fn should_parse_memory_size_to_bytes() {
+        assert_eq!(memory_size_to_bytes("36MB").unwrap(), 36 * 1024 * 1024);
+        assert_eq!(memory_size_to_bytes("2GB").unwrap(), 2 * 1024 * 1024 * 1024);
+        assert_eq!(memory_size_to_bytes("512KB").unwrap(), 512 * 1024);
+        assert_eq!(memory_size_to_bytes("42B").unwrap(), 42);
+        assert_eq!(memory_size_to_bytes("42").unwrap(), 42);
+        assert!(memory_size_to_bytes("MB").is_err());
+    }
+
+    #[test]
+    fn should_parse_key_value_to_summary_metrics() {
+        let mntr_3_6_resp = fs::read_to_string("../../fixtures/3.6/mntr.response").unwrap();
+        let mntr_3_6_map = tab_separated_bytes_to_key_value(&mntr_3_6_resp).unwrap();
+        let (summaries, remaining) =
+            key_value_to_summary_metrics(mntr_3_6_map, &mut ZK4LWParser::default()).unwrap();
+
+        // "zk_avg_latency", "zk_min_latency" and "zk_max_latency" are a family too
+        let latency = summaries.samples.get("latency").unwrap();
+        assert_eq!(latency.avg, 0.0);
+        assert_eq!(latency.count, None);
+
+        let snapshottime = summaries.samples.get("snapshottime").unwrap();
+        assert_eq!(snapshottime.avg, 5.0);
+        assert_eq!(snapshottime.count, Some(1));
+        assert_eq!(snapshottime.sum, Some(5));
+
+        let learner_qp_time = summaries
+            .time_samples
+            .get("10_learner_handler_qp_time_ms")
+            .unwrap();
+        assert_eq!(learner_qp_time.p50, Some(20));
+        assert_eq!(learner_qp_time.p95, Some(20));
+
+        assert_eq!(summaries.len(), 64);
+        assert_eq!(
+            remaining.get("zk_max_file_descriptor_count"),
+            Some(&"1048576")
+        );
+        assert_eq!(remaining.get("zk_min_proposal_size"), Some(&"-1"));
+        assert!(remaining.keys().all(|k| !k.starts_with("zk_avg_")));
+    }
 }
@@ -0,0 +1,135 @@
+//! Policies applied when parsing the responses of 4LW commands.
+
+use std::collections::HashMap;
+
+use crate::{errors::*, result::*};
+
+/// Policy applied when parsing the response of a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ZK4LWParsePolicy {
+    /// Fail if a required field is missing, or if any field fails to parse
+    #[default]
+    STRICT,
+    /// Keep going if a required field is missing, or if a field fails to parse:
+    /// the error is collected as a `ZK4LWParseWarning`, and the field keeps its default value
+    LENIENT,
+}
+
+/// Error encountered parsing a specific field of a response, tolerated by the lenient policy
+#[derive(Debug)]
+pub struct ZK4LWParseWarning {
+    /// Key of the field in the response (e.g. `zk_znode_count`)
+    pub field: String,
+    pub error: ZK4LWError,
+}
+
+/// Applies a `ZK4LWParsePolicy` while parsing a response, collecting warnings (if lenient)
+#[derive(Debug, Default)]
+pub struct ZK4LWParser {
+    policy: ZK4LWParsePolicy,
+    warnings: Vec<ZK4LWParseWarning>,
+}
+
+impl ZK4LWParser {
+    pub fn new(policy: ZK4LWParsePolicy) -> Self {
+        ZK4LWParser {
+            policy,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn policy(&self) -> ZK4LWParsePolicy {
+        self.policy
+    }
+
+    /// Warnings collected so far
+    pub fn warnings(&self) -> &[ZK4LWParseWarning] {
+        &self.warnings
+    }
+
+    pub fn into_warnings(self) -> Vec<ZK4LWParseWarning> {
+        self.warnings
+    }
+
+    /// Handles the result of parsing the given field
+    ///
+    /// Strict policy returns the error, while lenient policy collects it as a warning.
+    pub fn field(&mut self, field: &str, result: ZK4LWResult<()>) -> ZK4LWResult<()> {
+        match (result, self.policy) {
+            (Ok(()), _) => Ok(()),
+            (Err(error), ZK4LWParsePolicy::STRICT) => Err(error),
+            (Err(error), ZK4LWParsePolicy::LENIENT) => {
+                self.warnings.push(ZK4LWParseWarning {
+                    field: field.to_string(),
+                    error,
+                });
+                Ok(())
+            }
+        }
+    }
+
+    /// Checks that all the required fields are in the given key/value pairs of a response
+    ///
+    /// Strict policy fails with `ZK4LWError::MissingFieldError` at the first missing field,
+    /// while lenient policy collects a warning for each.
+    pub fn require_fields(
+        &mut self,
+        key_value: &HashMap<&str, &str>,
+        required_fields: &[&'static str],
+    ) -> ZK4LWResult<()> {
+        for field in required_fields {
+            if !key_value.contains_key(field) {
+                self.field(field, Err(ZK4LWError::MissingFieldError(field)))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::errors::ZK4LWError;
+    use crate::policy::{ZK4LWParsePolicy, ZK4LWParser};
+
+    #[test]
+    fn should_apply_parse_policy() {
+        let key_value: HashMap<&str, &str> = [("present", "1")].iter().cloned().collect();
+
+        let mut strict = ZK4LWParser::default();
+        assert_eq!(strict.policy(), ZK4LWParsePolicy::STRICT);
+        assert!(strict.require_fields(&key_value, &["present"]).is_ok());
+        match strict.require_fields(&key_value, &["present", "missing"]) {
+            Err(ZK4LWError::MissingFieldError(field)) => assert_eq!(field, "missing"),
+            _ => panic!("Expected MissingFieldError"),
+        }
+        assert!(strict
+            .field(
+                "present",
+                "x".parse::<i64>().map(|_| ()).map_err(Into::into)
+            )
+            .is_err());
+        assert!(strict.warnings().is_empty());
+
+        let mut lenient = ZK4LWParser::new(ZK4LWParsePolicy::LENIENT);
+        assert!(lenient
+            .require_fields(&key_value, &["present", "missing"])
+            .is_ok());
+        assert!(lenient
+            .field(
+                "present",
+                "x".parse::<i64>().map(|_| ()).map_err(Into::into)
+            )
+            .is_ok());
+        let warnings = lenient.into_warnings();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].field, "missing");
+        assert_eq!(warnings[1].field, "present");
+        match warnings[1].error {
+            ZK4LWError::ParseIntError(_) => {}
+            _ => panic!("Expected ParseIntError"),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Policies to retry the execution of 4LW commands that failed.
+
+use std::{fmt, time::Duration};
+
+use rand::Rng;
+
+use crate::errors::*;
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+const DEFAULT_INITIAL_DELAY: Duration = Duration::from_millis(100);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(5);
+const DEFAULT_MULTIPLIER: f64 = 2.0;
+const DEFAULT_JITTER: f64 = 0.5;
+
+/// Trait that defines if, and after how long, a failed execution of a command is retried
+///
+/// The client applies the policy around each execution: implement this trait to plug a custom one.
+pub trait ZK4LWRetryPolicy: fmt::Debug + Send + Sync {
+    /// Returns how long to wait before the next attempt, or `None` to give up
+    ///
+    /// # Arguments
+    /// * `attempt` - number of the attempt that failed, starting from `1`
+    /// * `error` - the error the attempt failed with
+    fn retry_delay(&self, attempt: u32, error: &ZK4LWError) -> Option<Duration>;
+}
+
+bitflags! {
+    /// Kinds of `ZK4LWError` that are worth retrying, as they are likely transient
+    pub struct ZK4LWRetryableErrors: u32 {
+        /// `ZK4LWError::IoError` (e.g. connection refused or reset, while a server restarts)
+        const IO = 1 << 0;
+        /// `ZK4LWError::Timeout`
+        const TIMEOUT = 1 << 1;
+        /// `ZK4LWError::NotServingError` (e.g. while a server joins the quorum)
+        const NOT_SERVING = 1 << 2;
+    }
+}
+
+/// By default, all the kinds of errors that are likely transient are retried
+impl Default for ZK4LWRetryableErrors {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl ZK4LWRetryableErrors {
+    /// Returns `true` if the given error is of a retryable kind
+    pub fn matches(&self, error: &ZK4LWError) -> bool {
+        match error {
+            ZK4LWError::IoError(_) => self.contains(Self::IO),
+            ZK4LWError::Timeout(_) => self.contains(Self::TIMEOUT),
+            ZK4LWError::NotServingError => self.contains(Self::NOT_SERVING),
+            _ => false,
+        }
+    }
+}
+
+/// Retry policy with exponential backoff and jitter
+///
+/// The delay before attempt `n + 1` is `initial_delay * multiplier^(n - 1)`, capped to `max_delay`,
+/// then reduced by a random fraction of up to `jitter` (e.g. `0.5` waits between 50% and 100% of it),
+/// so that many clients don't retry in lockstep against a recovering server.
+#[derive(Debug, Clone)]
+pub struct ZK4LWBackoffRetryPolicy {
+    /// Maximum number of attempts, including the first one
+    pub max_attempts: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Factor the delay grows by at each attempt: values below `1.0` (or NaN) are treated as `1.0`
+    pub multiplier: f64,
+    /// Fraction of the delay (between `0.0` and `1.0`) that is randomized
+    pub jitter: f64,
+    pub retryable_errors: ZK4LWRetryableErrors,
+}
+
+impl Default for ZK4LWBackoffRetryPolicy {
+    fn default() -> Self {
+        ZK4LWBackoffRetryPolicy {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_delay: DEFAULT_INITIAL_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            multiplier: DEFAULT_MULTIPLIER,
+            jitter: DEFAULT_JITTER,
+            retryable_errors: ZK4LWRetryableErrors::default(),
+        }
+    }
+}
+
+impl ZK4LWBackoffRetryPolicy {
+    pub fn new(max_attempts: u32) -> Self {
+        ZK4LWBackoffRetryPolicy {
+            max_attempts,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the delay before the attempt following the given one, before jitter is applied
+    pub fn backoff_delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let multiplier = if self.multiplier >= 1.0 {
+            self.multiplier
+        } else {
+            1.0
+        };
+        let delay = self.initial_delay.as_secs_f64() * multiplier.powi(exponent);
+
+        if delay.is_finite() && delay < self.max_delay.as_secs_f64() {
+            Duration::from_secs_f64(delay)
+        } else {
+            self.max_delay
+        }
+    }
+}
+
+impl ZK4LWRetryPolicy for ZK4LWBackoffRetryPolicy {
+    fn retry_delay(&self, attempt: u32, error: &ZK4LWError) -> Option<Duration> {
+        if attempt >= self.max_attempts || !self.retryable_errors.matches(error) {
+            return None;
+        }
+
+        let delay = self.backoff_delay(attempt);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter > 0.0 {
+            Some(delay.mul_f64(1.0 - jitter * rand::thread_rng().gen::<f64>()))
+        } else {
+            Some(delay)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, time::Duration};
+
+    use crate::errors::{ZK4LWError, ZK4LWPhase};
+    use crate::retry::{ZK4LWBackoffRetryPolicy, ZK4LWRetryPolicy, ZK4LWRetryableErrors};
+
+    #[test]
+    fn should_back_off_exponentially() {
+        let policy = ZK4LWBackoffRetryPolicy {
+            max_attempts: 10,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            multiplier: 2.0,
+            jitter: 0.0,
+            retryable_errors: ZK4LWRetryableErrors::default(),
+        };
+        let error = ZK4LWError::Timeout(ZK4LWPhase::CONNECT);
+
+        assert_eq!(
+            policy.retry_delay(1, &error),
+            Some(Duration::from_millis(100))
+        );
+        assert_eq!(
+            policy.retry_delay(2, &error),
+            Some(Duration::from_millis(200))
+        );
+        assert_eq!(
+            policy.retry_delay(3, &error),
+            Some(Duration::from_millis(400))
+        );
+        assert_eq!(policy.retry_delay(5, &error), Some(Duration::from_secs(1)));
+        assert_eq!(policy.retry_delay(10, &error), None);
+
+        // Jitter only ever shortens the delay
+        let policy = ZK4LWBackoffRetryPolicy {
+            jitter: 0.5,
+            ..policy
+        };
+        for _ in 0..100 {
+            let delay = policy.retry_delay(2, &error).unwrap();
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn should_retry_only_retryable_errors() {
+        let policy = ZK4LWBackoffRetryPolicy {
+            retryable_errors: ZK4LWRetryableErrors::IO | ZK4LWRetryableErrors::NOT_SERVING,
+            ..ZK4LWBackoffRetryPolicy::new(3)
+        };
+
+        let reset = io::Error::new(io::ErrorKind::ConnectionReset, "reset");
+        assert!(policy.retry_delay(1, &ZK4LWError::IoError(reset)).is_some());
+        assert!(policy
+            .retry_delay(1, &ZK4LWError::NotServingError)
+            .is_some());
+        assert!(policy
+            .retry_delay(1, &ZK4LWError::Timeout(ZK4LWPhase::READ))
+            .is_none());
+        assert!(policy
+            .retry_delay(1, &ZK4LWError::MissingFieldError("zk_version"))
+            .is_none());
+    }
+
+    #[test]
+    fn should_not_shrink_delay_with_multiplier_below_one() {
+        for multiplier in [-2.0, 0.5, f64::NAN].iter() {
+            let policy = ZK4LWBackoffRetryPolicy {
+                multiplier: *multiplier,
+                jitter: 0.0,
+                ..ZK4LWBackoffRetryPolicy::new(10)
+            };
+            for attempt in 1..5 {
+                assert_eq!(policy.backoff_delay(attempt), Duration::from_millis(100));
+            }
+        }
+    }
+}
@@ -1,21 +1,42 @@
 //! Representation of the State of a Zookeeper Server.
 
-use std::{default, fmt, str};
+use std::{fmt, str};
 
 use crate::errors::*;
 
-const STATE_LEADER: &'static str = "leader";
-const STATE_FOLLOWER: &'static str = "follower";
-const STATE_OBSERVER: &'static str = "observer";
-const STATE_STANDALONE: &'static str = "standalone";
+const STATE_LEADER: &str = "leader";
+const STATE_FOLLOWER: &str = "follower";
+const STATE_OBSERVER: &str = "observer";
+const STATE_STANDALONE: &str = "standalone";
+const STATE_READ_ONLY: &str = "read-only";
 
 /// The state of a Zookeeper server, as reported for example by the Monitor command
-#[derive(PartialEq)]
+#[derive(PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum ZK4LWServerState {
     LEADER,
     FOLLOWER,
     OBSERVER,
+    #[default]
     STANDALONE,
+    /// Partitioned from the quorum, and serving read-only requests (i.e. `readonlymode.enabled`)
+    #[allow(non_camel_case_types)]
+    #[cfg_attr(feature = "serde", serde(rename = "read-only"))]
+    READ_ONLY,
+}
+
+impl ZK4LWServerState {
+    /// Returns `true` if the server tracks the outstanding sessions of the ensemble
+    ///
+    /// Only the "Leader" (or a "Standalone" server) does: commands like `dump`
+    /// fail with `ZK4LWError::NotLeaderError` when executed against any other server.
+    pub fn tracks_sessions(&self) -> bool {
+        matches!(
+            self,
+            ZK4LWServerState::LEADER | ZK4LWServerState::STANDALONE
+        )
+    }
 }
 
 impl str::FromStr for ZK4LWServerState {
@@ -27,6 +48,7 @@ impl str::FromStr for ZK4LWServerState {
             STATE_FOLLOWER => Ok(ZK4LWServerState::FOLLOWER),
             STATE_OBSERVER => Ok(ZK4LWServerState::OBSERVER),
             STATE_STANDALONE => Ok(ZK4LWServerState::STANDALONE),
+            STATE_READ_ONLY => Ok(ZK4LWServerState::READ_ONLY),
             _ => Err(ZK4LWError::ParseStringError(s.to_string())),
         }
     }
@@ -39,12 +61,7 @@ impl fmt::Debug for ZK4LWServerState {
             ZK4LWServerState::FOLLOWER => write!(f, "{}", STATE_FOLLOWER),
             ZK4LWServerState::OBSERVER => write!(f, "{}", STATE_OBSERVER),
             ZK4LWServerState::STANDALONE => write!(f, "{}", STATE_STANDALONE),
+            ZK4LWServerState::READ_ONLY => write!(f, "{}", STATE_READ_ONLY),
         }
     }
 }
-
-impl default::Default for ZK4LWServerState {
-    fn default() -> Self {
-        ZK4LWServerState::STANDALONE
-    }
-}
@@ -0,0 +1,130 @@
+//! Transport that answers commands with canned responses, without connecting to any server.
+
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use crate::{result::*, transport::*};
+
+/// Transport that answers commands with canned responses, without connecting to any server
+///
+/// This is useful to test tooling built on the client. Requests without a canned response
+/// get an empty one, like the server does for unknown commands.
+#[derive(Debug, Clone, Default)]
+pub struct ZK4LWMemoryTransport {
+    responses: Arc<HashMap<Vec<u8>, Vec<u8>>>,
+    requests: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+impl ZK4LWMemoryTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answer the given request (e.g. `mntr`) with the given response
+    pub fn with_response<Q: Into<Vec<u8>>, R: Into<Vec<u8>>>(
+        mut self,
+        request_body: Q,
+        response_body: R,
+    ) -> Self {
+        Arc::make_mut(&mut self.responses).insert(request_body.into(), response_body.into());
+        self
+    }
+
+    /// Requests received so far, in order (also via clients the transport was cloned into)
+    pub fn requests(&self) -> Vec<Vec<u8>> {
+        self.requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl ZK4LWTransport for ZK4LWMemoryTransport {
+    fn open(
+        &self,
+        _host: &str,
+        _port: u16,
+        _timeout: Option<Duration>,
+    ) -> ZK4LWResult<Box<dyn ZK4LWConnection>> {
+        Ok(Box::new(ZK4LWMemoryConnection {
+            transport: self.clone(),
+            response: io::Cursor::new(Vec::new()),
+        }))
+    }
+}
+
+/// Connection opened by `ZK4LWMemoryTransport`
+struct ZK4LWMemoryConnection {
+    transport: ZK4LWMemoryTransport,
+    response: io::Cursor<Vec<u8>>,
+}
+
+impl ZK4LWConnection for ZK4LWMemoryConnection {
+    fn write_request(
+        &mut self,
+        request_body: &[u8],
+        _timeout: Option<Duration>,
+    ) -> ZK4LWResult<()> {
+        self.transport
+            .requests
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(request_body.to_vec());
+
+        let response_body = self.transport.responses.get(request_body);
+        self.response = io::Cursor::new(response_body.cloned().unwrap_or_default());
+        Ok(())
+    }
+
+    fn read_response(
+        &mut self,
+        buffer: &mut [u8],
+        _timeout: Option<Duration>,
+    ) -> ZK4LWResult<usize> {
+        Ok(self.response.read(buffer)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::client::ZK4LWClient;
+    use crate::commands::mntr::ZK4LWMonitor;
+    use crate::commands::ruok::{ZK4LWRuok, ZK4LWRuokResponse};
+    use crate::commands::tracemask::ZK4LWSetTraceMask;
+    use crate::state::ZK4LWServerState::LEADER;
+    use crate::transport::memory::ZK4LWMemoryTransport;
+
+    #[test]
+    fn should_answer_with_canned_responses() {
+        let mntr_resp_body = fs::read("../../fixtures/3.6/mntr.response").unwrap();
+        let transport = ZK4LWMemoryTransport::new()
+            .with_response("ruok", "imok")
+            .with_response("mntr", mntr_resp_body);
+        let client = ZK4LWClient::builder().transport(transport.clone()).build();
+
+        assert_eq!(
+            client.execute::<ZK4LWRuok>().unwrap(),
+            ZK4LWRuokResponse::IMOK
+        );
+        let mntr_resp = client.execute::<ZK4LWMonitor>().unwrap();
+        assert!(mntr_resp.server_state == LEADER);
+        assert_eq!(mntr_resp.znode_count, 5);
+
+        // Requests without a canned response get an empty one
+        assert!(client
+            .execute_command(&ZK4LWSetTraceMask::new(Default::default()))
+            .is_err());
+
+        let requests = transport.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0], b"ruok");
+        assert_eq!(requests[1], b"mntr");
+        assert!(requests[2].starts_with(b"stmk"));
+    }
+}
@@ -0,0 +1,94 @@
+//! Transports the Zookeeper "Four Letter Words" client sends commands, and receives responses, over.
+//!
+//! By default the client connects to the server via TCP (see `tcp::ZK4LWTcpTransport`):
+//! implement `ZK4LWTransport` to plug a different one (e.g. to tunnel commands).
+//! Both the TCP and TLS transports can tunnel connections through a `proxy::ZK4LWProxy`.
+
+pub mod memory;
+pub mod proxy;
+pub mod tcp;
+#[cfg(feature = "tls")]
+pub mod tls;
+#[cfg(unix)]
+pub mod unix;
+
+use std::{
+    fmt,
+    io::{self, Read, Write},
+    time::Duration,
+};
+
+use crate::{client::*, errors::*, result::*};
+
+/// Trait that defines how the client opens connections to a server
+pub trait ZK4LWTransport: fmt::Debug + Send + Sync {
+    /// Opens a connection to the server, to execute a single command
+    ///
+    /// # Arguments
+    /// * `host` - host in the ZK Ensemble the client sends commands to
+    /// * `port` - port the client sends commands on
+    /// * `timeout` - maximum time to wait for the connection to be established, if any
+    fn open(
+        &self,
+        host: &str,
+        port: u16,
+        timeout: Option<Duration>,
+    ) -> ZK4LWResult<Box<dyn ZK4LWConnection>>;
+}
+
+/// Trait that defines a connection to a server, opened by a `ZK4LWTransport`
+pub trait ZK4LWConnection {
+    /// Sends the whole request of the command
+    ///
+    /// # Arguments
+    /// * `request_body` - the bytes of the request (see `ZK4LWCommand::request_body`)
+    /// * `timeout` - maximum time to wait for the request to be sent, if any
+    fn write_request(&mut self, request_body: &[u8], timeout: Option<Duration>) -> ZK4LWResult<()>;
+
+    /// Reads the next chunk of the response into the given buffer, and returns its size
+    ///
+    /// Returns `0` once the server has sent the whole response (e.g. it closed the connection).
+    ///
+    /// # Arguments
+    /// * `buffer` - the buffer to read the chunk into
+    /// * `timeout` - maximum time to wait for the chunk, if any
+    fn read_response(&mut self, buffer: &mut [u8], timeout: Option<Duration>)
+        -> ZK4LWResult<usize>;
+}
+
+/// Stream that supports timeouts (e.g. a socket), to build connections on
+pub(crate) trait ZK4LWStream: Read + Write {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+}
+
+/// Connection over a stream that supports timeouts
+pub(crate) struct ZK4LWStreamConnection<S>(pub(crate) S);
+
+impl<S: ZK4LWStream> ZK4LWConnection for ZK4LWStreamConnection<S> {
+    fn write_request(&mut self, request_body: &[u8], timeout: Option<Duration>) -> ZK4LWResult<()> {
+        self.0.set_write_timeout(timeout)?;
+        self.0
+            .write_all(request_body)
+            .and_then(|_| self.0.flush())
+            .map_err(|e| phase_error(e, ZK4LWPhase::WRITE))
+    }
+
+    fn read_response(
+        &mut self,
+        buffer: &mut [u8],
+        timeout: Option<Duration>,
+    ) -> ZK4LWResult<usize> {
+        self.0.set_read_timeout(timeout)?;
+        loop {
+            match self.0.read(buffer) {
+                Ok(n) => return Ok(n),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                // TLS servers can close the connection without notifying it first
+                Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(0),
+                Err(e) => return Err(phase_error(e, ZK4LWPhase::READ)),
+            }
+        }
+    }
+}
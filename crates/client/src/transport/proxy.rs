@@ -0,0 +1,542 @@
+//! Proxies to tunnel the connections of the Zookeeper "Four Letter Words" client through.
+//!
+//! Supported are SOCKS5 (e.g. a bastion into an isolated network, see RFC 1928),
+//! with optional username/password authentication (see RFC 1929), and HTTP `CONNECT`.
+
+use std::{
+    fmt,
+    io::{Read, Write},
+    net,
+};
+
+use crate::{client::*, errors::*, result::*};
+
+const SOCKS5_VERSION: u8 = 0x05;
+const SOCKS5_AUTH_NONE: u8 = 0x00;
+const SOCKS5_AUTH_USERNAME_PASSWORD: u8 = 0x02;
+const SOCKS5_AUTH_NO_ACCEPTABLE: u8 = 0xFF;
+const SOCKS5_USERNAME_PASSWORD_VERSION: u8 = 0x01;
+const SOCKS5_USERNAME_PASSWORD_SUCCEEDED: u8 = 0x00;
+const SOCKS5_CMD_CONNECT: u8 = 0x01;
+const SOCKS5_ATYP_IPV4: u8 = 0x01;
+const SOCKS5_ATYP_DOMAIN: u8 = 0x03;
+const SOCKS5_ATYP_IPV6: u8 = 0x04;
+const SOCKS5_REPLY_SUCCEEDED: u8 = 0x00;
+
+const HTTP_HEADERS_END: &[u8] = b"\r\n\r\n";
+const HTTP_HEADERS_MAX_SIZE: usize = 8192;
+
+/// Credentials to authenticate with a proxy
+#[derive(Clone, PartialEq, Eq)]
+pub struct ZK4LWProxyCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+/// The password is never printed
+impl fmt::Debug for ZK4LWProxyCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ZK4LWProxyCredentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Proxy to tunnel the connections to the server through
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZK4LWProxy {
+    /// SOCKS5 proxy, with optional username/password authentication
+    ///
+    /// The host of the server is resolved by the proxy, so it can be a name only known
+    /// inside the network behind it.
+    SOCKS5 {
+        host: String,
+        port: u16,
+        credentials: Option<ZK4LWProxyCredentials>,
+    },
+    /// HTTP proxy that supports the `CONNECT` method
+    HTTP { host: String, port: u16 },
+}
+
+impl ZK4LWProxy {
+    /// SOCKS5 proxy, without authentication
+    pub fn socks5<S: Into<String>>(host: S, port: u16) -> Self {
+        ZK4LWProxy::SOCKS5 {
+            host: host.into(),
+            port,
+            credentials: None,
+        }
+    }
+
+    /// SOCKS5 proxy, with username/password authentication
+    pub fn socks5_with_credentials<S: Into<String>, U: Into<String>, P: Into<String>>(
+        host: S,
+        port: u16,
+        username: U,
+        password: P,
+    ) -> Self {
+        ZK4LWProxy::SOCKS5 {
+            host: host.into(),
+            port,
+            credentials: Some(ZK4LWProxyCredentials {
+                username: username.into(),
+                password: password.into(),
+            }),
+        }
+    }
+
+    /// HTTP proxy that supports the `CONNECT` method
+    pub fn http<S: Into<String>>(host: S, port: u16) -> Self {
+        ZK4LWProxy::HTTP {
+            host: host.into(),
+            port,
+        }
+    }
+
+    /// Host of the proxy
+    pub fn host(&self) -> &str {
+        match self {
+            ZK4LWProxy::SOCKS5 { host, .. } | ZK4LWProxy::HTTP { host, .. } => host,
+        }
+    }
+
+    /// Port of the proxy
+    pub fn port(&self) -> u16 {
+        match self {
+            ZK4LWProxy::SOCKS5 { port, .. } | ZK4LWProxy::HTTP { port, .. } => *port,
+        }
+    }
+
+    /// Asks the proxy, over the given stream connected to it, to tunnel it to the given host and port
+    pub(crate) fn tunnel<S: Read + Write>(
+        &self,
+        stream: &mut S,
+        host: &str,
+        port: u16,
+    ) -> ZK4LWResult<()> {
+        let result = match self {
+            ZK4LWProxy::SOCKS5 { credentials, .. } => {
+                socks5_tunnel(stream, credentials.as_ref(), host, port)
+            }
+            ZK4LWProxy::HTTP { .. } => http_tunnel(stream, host, port),
+        };
+
+        result.map_err(|e| match e {
+            ZK4LWError::IoError(e) => phase_error(e, ZK4LWPhase::CONNECT),
+            e => e,
+        })
+    }
+}
+
+fn socks5_tunnel<S: Read + Write>(
+    stream: &mut S,
+    credentials: Option<&ZK4LWProxyCredentials>,
+    host: &str,
+    port: u16,
+) -> ZK4LWResult<()> {
+    // Negotiate the authentication method
+    let greeting: &[u8] = match credentials {
+        Some(_) => &[
+            SOCKS5_VERSION,
+            2,
+            SOCKS5_AUTH_NONE,
+            SOCKS5_AUTH_USERNAME_PASSWORD,
+        ],
+        None => &[SOCKS5_VERSION, 1, SOCKS5_AUTH_NONE],
+    };
+    stream.write_all(greeting)?;
+
+    let mut choice = [0u8; 2];
+    stream.read_exact(&mut choice)?;
+    if choice[0] != SOCKS5_VERSION {
+        return Err(proxy_error(
+            "SOCKS5 proxy replied with an unsupported version",
+        ));
+    }
+    match (choice[1], credentials) {
+        (SOCKS5_AUTH_NONE, _) => {}
+        (SOCKS5_AUTH_USERNAME_PASSWORD, Some(credentials)) => {
+            socks5_authenticate(stream, credentials)?
+        }
+        (SOCKS5_AUTH_NO_ACCEPTABLE, _) => {
+            return Err(proxy_error(
+                "SOCKS5 proxy accepts none of the authentication methods offered",
+            ))
+        }
+        _ => {
+            return Err(proxy_error(
+                "SOCKS5 proxy chose an authentication method that wasn't offered",
+            ))
+        }
+    }
+
+    // Request the connection to the server
+    let mut request = vec![SOCKS5_VERSION, SOCKS5_CMD_CONNECT, 0x00];
+    match host.parse::<net::IpAddr>() {
+        Ok(net::IpAddr::V4(ip)) => {
+            request.push(SOCKS5_ATYP_IPV4);
+            request.extend_from_slice(&ip.octets());
+        }
+        Ok(net::IpAddr::V6(ip)) => {
+            request.push(SOCKS5_ATYP_IPV6);
+            request.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            request.push(SOCKS5_ATYP_DOMAIN);
+            request.push(socks5_len(host, "host")?);
+            request.extend_from_slice(host.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request)?;
+
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply)?;
+    if reply[0] != SOCKS5_VERSION {
+        return Err(proxy_error(
+            "SOCKS5 proxy replied with an unsupported version",
+        ));
+    }
+    if reply[1] != SOCKS5_REPLY_SUCCEEDED {
+        return Err(ZK4LWError::ProxyError(format!(
+            "SOCKS5 proxy failed to connect to {}:{}: {}",
+            host,
+            port,
+            socks5_reply_message(reply[1])
+        )));
+    }
+
+    // Skip the address the proxy bound to connect to the server
+    let bound_addr_len = match reply[3] {
+        SOCKS5_ATYP_IPV4 => 4,
+        SOCKS5_ATYP_IPV6 => 16,
+        SOCKS5_ATYP_DOMAIN => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len)?;
+            len[0] as usize
+        }
+        _ => {
+            return Err(proxy_error(
+                "SOCKS5 proxy replied with an unknown address type",
+            ))
+        }
+    };
+    let mut bound_addr = vec![0u8; bound_addr_len + 2];
+    stream.read_exact(&mut bound_addr)?;
+
+    Ok(())
+}
+
+fn socks5_authenticate<S: Read + Write>(
+    stream: &mut S,
+    credentials: &ZK4LWProxyCredentials,
+) -> ZK4LWResult<()> {
+    let mut request = vec![SOCKS5_USERNAME_PASSWORD_VERSION];
+    request.push(socks5_len(&credentials.username, "username")?);
+    request.extend_from_slice(credentials.username.as_bytes());
+    request.push(socks5_len(&credentials.password, "password")?);
+    request.extend_from_slice(credentials.password.as_bytes());
+    stream.write_all(&request)?;
+
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply)?;
+    if reply[0] != SOCKS5_USERNAME_PASSWORD_VERSION {
+        return Err(proxy_error(
+            "SOCKS5 proxy replied with an unsupported username/password authentication version",
+        ));
+    }
+    if reply[1] != SOCKS5_USERNAME_PASSWORD_SUCCEEDED {
+        return Err(proxy_error("SOCKS5 proxy refused the username/password"));
+    }
+
+    Ok(())
+}
+
+/// Returns the length of the given field, as SOCKS5 encodes it (i.e. up to 255 bytes)
+fn socks5_len(field: &str, name: &str) -> ZK4LWResult<u8> {
+    if field.len() > u8::MAX as usize {
+        return Err(ZK4LWError::ProxyError(format!(
+            "SOCKS5 {} is longer than 255 bytes",
+            name
+        )));
+    }
+
+    Ok(field.len() as u8)
+}
+
+fn socks5_reply_message(reply: u8) -> &'static str {
+    match reply {
+        0x01 => "general failure",
+        0x02 => "connection not allowed by ruleset",
+        0x03 => "network unreachable",
+        0x04 => "host unreachable",
+        0x05 => "connection refused",
+        0x06 => "TTL expired",
+        0x07 => "command not supported",
+        0x08 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+fn http_tunnel<S: Read + Write>(stream: &mut S, host: &str, port: u16) -> ZK4LWResult<()> {
+    let authority = match host.parse::<net::Ipv6Addr>() {
+        Ok(_) => format!("[{}]:{}", host, port),
+        Err(_) => format!("{}:{}", host, port),
+    };
+    let request = format!("CONNECT {0} HTTP/1.1\r\nHost: {0}\r\n\r\n", authority);
+    stream.write_all(request.as_bytes())?;
+
+    // Read the response headers one byte at a time, not to consume any of the tunnelled data
+    let mut response = Vec::new();
+    let mut byte = [0u8; 1];
+    while !response.ends_with(HTTP_HEADERS_END) {
+        if response.len() >= HTTP_HEADERS_MAX_SIZE {
+            return Err(proxy_error("HTTP proxy response headers are too large"));
+        }
+        stream.read_exact(&mut byte)?;
+        response.push(byte[0]);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') => Ok(()),
+        _ => Err(ZK4LWError::ProxyError(format!(
+            "HTTP proxy failed to connect to {}: {}",
+            authority, status_line
+        ))),
+    }
+}
+
+fn proxy_error(message: &str) -> ZK4LWError {
+    ZK4LWError::ProxyError(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{self, Read, Write},
+        net, thread,
+    };
+
+    use crate::client::ZK4LWClient;
+    use crate::commands::ruok::{ZK4LWRuok, ZK4LWRuokResponse};
+    use crate::errors::ZK4LWError;
+    use crate::transport::proxy::ZK4LWProxy;
+
+    /// Spawns a SOCKS5 proxy stand-in that serves a single connection, and answers `ruok`
+    /// itself (as the server would) once the tunnel is established
+    fn serve_socks5_once(
+        credentials: Option<(&'static str, &'static str)>,
+    ) -> (u16, thread::JoinHandle<()>) {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let proxy = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut greeting = [0u8; 2];
+            stream.read_exact(&mut greeting).unwrap();
+            let mut methods = vec![0u8; greeting[1] as usize];
+            stream.read_exact(&mut methods).unwrap();
+
+            if let Some((username, password)) = credentials {
+                assert!(methods.contains(&0x02));
+                stream.write_all(&[0x05, 0x02]).unwrap();
+
+                let mut auth = [0u8; 2];
+                stream.read_exact(&mut auth).unwrap();
+                let mut received_username = vec![0u8; auth[1] as usize];
+                stream.read_exact(&mut received_username).unwrap();
+                let mut len = [0u8; 1];
+                stream.read_exact(&mut len).unwrap();
+                let mut received_password = vec![0u8; len[0] as usize];
+                stream.read_exact(&mut received_password).unwrap();
+
+                if received_username != username.as_bytes()
+                    || received_password != password.as_bytes()
+                {
+                    stream.write_all(&[0x01, 0x01]).unwrap();
+                    return;
+                }
+                stream.write_all(&[0x01, 0x00]).unwrap();
+            } else {
+                stream.write_all(&[0x05, 0x00]).unwrap();
+            }
+
+            // The server is resolved by the proxy
+            let mut request = [0u8; 5];
+            stream.read_exact(&mut request).unwrap();
+            assert_eq!(&request[..4], &[0x05, 0x01, 0x00, 0x03]);
+            let mut target = vec![0u8; request[4] as usize + 2];
+            stream.read_exact(&mut target).unwrap();
+            assert_eq!(&target[..], b"zk1.internal\x08\x85");
+            stream
+                .write_all(&[0x05, 0x00, 0x00, 0x01, 127, 0, 0, 1, 0x08, 0x85])
+                .unwrap();
+
+            let mut request = [0u8; 4];
+            stream.read_exact(&mut request).unwrap();
+            assert_eq!(&request, b"ruok");
+            stream.write_all(b"imok").unwrap();
+        });
+
+        (port, proxy)
+    }
+
+    /// Spawns an HTTP proxy stand-in that serves a single connection with the given status line,
+    /// and answers `ruok` itself (as the server would) if the tunnel is established
+    fn serve_http_once(status_line: &'static str) -> (u16, thread::JoinHandle<()>) {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let proxy = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut request = Vec::new();
+            let mut byte = [0u8; 1];
+            while !request.ends_with(b"\r\n\r\n") {
+                stream.read_exact(&mut byte).unwrap();
+                request.push(byte[0]);
+            }
+            assert!(request.starts_with(b"CONNECT zk1.internal:2181 HTTP/1.1\r\n"));
+            stream
+                .write_all(format!("{}\r\nServer: stand-in\r\n\r\n", status_line).as_bytes())
+                .unwrap();
+
+            if status_line.contains(" 200 ") {
+                let mut request = [0u8; 4];
+                stream.read_exact(&mut request).unwrap();
+                assert_eq!(&request, b"ruok");
+                stream.write_all(b"imok").unwrap();
+            }
+        });
+
+        (port, proxy)
+    }
+
+    /// Stream connected to a proxy stand-in that sends the given replies, whatever it receives
+    struct ScriptedStream(io::Cursor<Vec<u8>>);
+
+    impl Read for ScriptedStream {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.0.read(buf)
+        }
+    }
+
+    impl Write for ScriptedStream {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn client_via(proxy: ZK4LWProxy) -> ZK4LWClient {
+        ZK4LWClient::builder()
+            .host("zk1.internal")
+            .port(2181)
+            .proxy(proxy)
+            .build()
+    }
+
+    #[test]
+    fn should_tunnel_through_socks5_proxy() {
+        let (port, proxy) = serve_socks5_once(None);
+        assert_eq!(
+            client_via(ZK4LWProxy::socks5("127.0.0.1", port))
+                .execute::<ZK4LWRuok>()
+                .unwrap(),
+            ZK4LWRuokResponse::IMOK
+        );
+        proxy.join().unwrap();
+
+        let (port, proxy) = serve_socks5_once(Some(("zk4lw", "s3cr3t")));
+        let client = client_via(ZK4LWProxy::socks5_with_credentials(
+            "127.0.0.1",
+            port,
+            "zk4lw",
+            "s3cr3t",
+        ));
+        assert_eq!(
+            client.execute::<ZK4LWRuok>().unwrap(),
+            ZK4LWRuokResponse::IMOK
+        );
+        proxy.join().unwrap();
+
+        let (port, proxy) = serve_socks5_once(Some(("zk4lw", "s3cr3t")));
+        let client = client_via(ZK4LWProxy::socks5_with_credentials(
+            "127.0.0.1",
+            port,
+            "zk4lw",
+            "wrong",
+        ));
+        match client.execute::<ZK4LWRuok>() {
+            Err(ZK4LWError::ProxyError(_)) => {}
+            _ => panic!("Expected ProxyError"),
+        }
+        proxy.join().unwrap();
+    }
+
+    #[test]
+    fn should_refuse_socks5_replies_with_unsupported_versions() {
+        let connect_reply = [0x05, 0x00, 0x00, 0x01, 127, 0, 0, 1, 0x08, 0x85];
+        let proxy = ZK4LWProxy::socks5("bastion", 1080);
+        let proxy_with_credentials =
+            ZK4LWProxy::socks5_with_credentials("bastion", 1080, "zk4lw", "s3cr3t");
+
+        // Reply to the connection request from a SOCKS4 proxy
+        let mut stream = ScriptedStream(io::Cursor::new(
+            [&[0x05, 0x00], &[0x04][..], &connect_reply[1..]].concat(),
+        ));
+        match proxy.tunnel(&mut stream, "zk1.internal", 2181) {
+            Err(ZK4LWError::ProxyError(message)) => assert!(message.contains("version")),
+            _ => panic!("Expected ProxyError"),
+        }
+
+        // Reply to the authentication with the SOCKS version, instead of the sub-negotiation one
+        let mut stream = ScriptedStream(io::Cursor::new(
+            [&[0x05, 0x02], &[0x05, 0x00][..], &connect_reply[..]].concat(),
+        ));
+        match proxy_with_credentials.tunnel(&mut stream, "zk1.internal", 2181) {
+            Err(ZK4LWError::ProxyError(message)) => assert!(message.contains("version")),
+            _ => panic!("Expected ProxyError"),
+        }
+
+        // The same replies, with the expected versions
+        let mut stream = ScriptedStream(io::Cursor::new(
+            [&[0x05, 0x02], &[0x01, 0x00][..], &connect_reply[..]].concat(),
+        ));
+        proxy_with_credentials
+            .tunnel(&mut stream, "zk1.internal", 2181)
+            .unwrap();
+    }
+
+    #[test]
+    fn should_tunnel_through_http_proxy() {
+        let (port, proxy) = serve_http_once("HTTP/1.1 200 Connection established");
+        assert_eq!(
+            client_via(ZK4LWProxy::http("127.0.0.1", port))
+                .execute::<ZK4LWRuok>()
+                .unwrap(),
+            ZK4LWRuokResponse::IMOK
+        );
+        proxy.join().unwrap();
+
+        let (port, proxy) = serve_http_once("HTTP/1.1 403 Forbidden");
+        match client_via(ZK4LWProxy::http("127.0.0.1", port)).execute::<ZK4LWRuok>() {
+            Err(ZK4LWError::ProxyError(message)) => assert!(message.contains("403 Forbidden")),
+            _ => panic!("Expected ProxyError"),
+        }
+        proxy.join().unwrap();
+    }
+
+    #[test]
+    fn should_not_print_proxy_password() {
+        let proxy = ZK4LWProxy::socks5_with_credentials("bastion", 1080, "zk4lw", "s3cr3t");
+        assert!(!format!("{:?}", proxy).contains("s3cr3t"));
+    }
+}
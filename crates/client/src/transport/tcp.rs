@@ -0,0 +1,105 @@
+//! Transport that connects to the server via TCP.
+
+use std::{
+    io,
+    net::{self, ToSocketAddrs},
+    time::{Duration, Instant},
+};
+
+use crate::{client::*, errors::*, result::*, transport::proxy::*, transport::*};
+
+/// Transport that connects to the server via TCP (the default), optionally through a proxy
+#[derive(Debug, Clone, Default)]
+pub struct ZK4LWTcpTransport {
+    proxy: Option<ZK4LWProxy>,
+}
+
+impl ZK4LWTcpTransport {
+    pub const fn new() -> Self {
+        ZK4LWTcpTransport { proxy: None }
+    }
+
+    /// Tunnel the connections to the server through the given proxy
+    pub fn with_proxy(mut self, proxy: ZK4LWProxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub fn proxy(&self) -> Option<&ZK4LWProxy> {
+        self.proxy.as_ref()
+    }
+
+    /// Connects to the server (through the proxy, if any) within the given timeout
+    pub(crate) fn connect(
+        &self,
+        host: &str,
+        port: u16,
+        timeout: Option<Duration>,
+    ) -> ZK4LWResult<net::TcpStream> {
+        match &self.proxy {
+            Some(proxy) => {
+                let mut stream = connect(proxy.host(), proxy.port(), timeout)?;
+                stream.set_read_timeout(timeout)?;
+                stream.set_write_timeout(timeout)?;
+                proxy.tunnel(&mut stream, host, port)?;
+                Ok(stream)
+            }
+            None => connect(host, port, timeout),
+        }
+    }
+}
+
+impl ZK4LWTransport for ZK4LWTcpTransport {
+    fn open(
+        &self,
+        host: &str,
+        port: u16,
+        timeout: Option<Duration>,
+    ) -> ZK4LWResult<Box<dyn ZK4LWConnection>> {
+        let stream = self.connect(host, port, timeout)?;
+        Ok(Box::new(ZK4LWStreamConnection(stream)))
+    }
+}
+
+impl ZK4LWStream for net::TcpStream {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        net::TcpStream::set_read_timeout(self, timeout)
+    }
+
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        net::TcpStream::set_write_timeout(self, timeout)
+    }
+}
+
+/// Connects to the given host, trying each of the addresses it resolves to
+fn connect(host: &str, port: u16, timeout: Option<Duration>) -> ZK4LWResult<net::TcpStream> {
+    let stream = match timeout {
+        Some(timeout) => connect_timeout(host, port, timeout)?,
+        None => net::TcpStream::connect((host, port))?,
+    };
+    stream.set_nodelay(true)?;
+
+    Ok(stream)
+}
+
+/// Connects to the given host within the given timeout, shared by all the addresses it resolves to
+fn connect_timeout(host: &str, port: u16, timeout: Duration) -> ZK4LWResult<net::TcpStream> {
+    let deadline = Instant::now() + timeout;
+
+    let mut last_err = None;
+    for addr in (host, port).to_socket_addrs()? {
+        let remaining = match deadline.checked_duration_since(Instant::now()) {
+            Some(remaining) if remaining > Duration::from_millis(0) => remaining,
+            _ => return Err(ZK4LWError::Timeout(ZK4LWPhase::CONNECT)),
+        };
+        match net::TcpStream::connect_timeout(&addr, remaining) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_err = Some(e),
+        }
+    }
+
+    Err(match last_err {
+        Some(e) => phase_error(e, ZK4LWPhase::CONNECT),
+        None => io::Error::new(io::ErrorKind::NotFound, "Host resolved to no address").into(),
+    })
+}
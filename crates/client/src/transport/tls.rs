@@ -0,0 +1,460 @@
+//! Transport that connects to the server via TLS, based on rustls.
+//!
+//! Since ZK 3.5.x, servers can serve 4LW commands over their TLS client port
+//! (i.e. `secureClientPort`), next to (or in place of) the plaintext one.
+//!
+//! NOTE: only available with the `tls` feature.
+
+use std::{convert::TryFrom, fs, io, net, path::Path, sync::Arc, time::Duration};
+
+use rustls::{
+    client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+    crypto::{self, CryptoProvider},
+    pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime},
+    ClientConfig, ClientConnection, DigitallySignedStruct, RootCertStore, SignatureScheme,
+    StreamOwned,
+};
+
+use crate::{
+    client::*, errors::*, result::*, transport::proxy::*, transport::tcp::*, transport::*,
+};
+
+/// TLS configuration of the client
+///
+/// By default the certificate of the server is verified against the well-known (WebPKI)
+/// certificate authorities, and no client certificate is presented.
+#[derive(Debug, Clone, Default)]
+pub struct ZK4LWTlsConfig {
+    ca_certs: Vec<CertificateDer<'static>>,
+    client_cert: Option<(Vec<CertificateDer<'static>>, Arc<PrivateKeyDer<'static>>)>,
+    server_name: Option<String>,
+    insecure: bool,
+}
+
+impl ZK4LWTlsConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust the certificate authorities in the given PEM, instead of the well-known ones
+    ///
+    /// Can be called multiple times, to trust more than one certificate authority.
+    pub fn ca_cert_pem(mut self, pem: &[u8]) -> ZK4LWResult<Self> {
+        self.ca_certs.extend(certs_from_pem(pem)?);
+        Ok(self)
+    }
+
+    /// Trust the certificate authorities in the PEM file at the given path
+    pub fn ca_cert_file<P: AsRef<Path>>(self, path: P) -> ZK4LWResult<Self> {
+        self.ca_cert_pem(&fs::read(path)?)
+    }
+
+    /// Present the given client certificate (chain) and private key, both in PEM,
+    /// to servers that require client authentication (i.e. `ssl.clientAuth`)
+    pub fn client_cert_pem(mut self, cert_pem: &[u8], key_pem: &[u8]) -> ZK4LWResult<Self> {
+        let certs = certs_from_pem(cert_pem)?;
+        let key = rustls_pemfile::private_key(&mut &key_pem[..])?
+            .ok_or_else(|| ZK4LWError::TlsError("No private key found in PEM".into()))?;
+
+        self.client_cert = Some((certs, Arc::new(key)));
+        Ok(self)
+    }
+
+    /// Present the client certificate (chain) and private key in the PEM files at the given paths
+    pub fn client_cert_files<P: AsRef<Path>>(self, cert_path: P, key_path: P) -> ZK4LWResult<Self> {
+        self.client_cert_pem(&fs::read(cert_path)?, &fs::read(key_path)?)
+    }
+
+    /// Name of the server, sent via SNI and verified against its certificate
+    /// (default: the host the client connects to)
+    ///
+    /// This is useful when connecting via IP, or via a hostname not in the certificate.
+    pub fn server_name<S: Into<String>>(mut self, server_name: S) -> Self {
+        self.server_name = Some(server_name.into());
+        self
+    }
+
+    /// Accept any certificate from the server, without verifying it
+    ///
+    /// WARNING: this defeats the purpose of TLS, and should only be used in labs
+    /// (e.g. against servers with self-signed certificates).
+    pub fn insecure(mut self, insecure: bool) -> Self {
+        self.insecure = insecure;
+        self
+    }
+
+    /// Starts a TLS connection to the given host, to be driven over an established TCP stream
+    fn client_connection(&self, host: &str) -> ZK4LWResult<ClientConnection> {
+        let name = self.server_name.as_deref().unwrap_or(host);
+        let server_name = ServerName::try_from(name.to_string())
+            .map_err(|_| ZK4LWError::TlsError(format!("Invalid server name: {}", name)))?;
+
+        ClientConnection::new(self.client_config()?, server_name).map_err(tls_error)
+    }
+
+    fn client_config(&self) -> ZK4LWResult<Arc<ClientConfig>> {
+        let provider = Arc::new(crypto::ring::default_provider());
+        let builder = ClientConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(tls_error)?;
+
+        let builder = if self.insecure {
+            builder
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(InsecureServerCertVerifier(provider)))
+        } else {
+            let mut roots = RootCertStore::empty();
+            if self.ca_certs.is_empty() {
+                roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+            }
+            for ca_cert in &self.ca_certs {
+                roots.add(ca_cert.clone()).map_err(tls_error)?;
+            }
+            builder.with_root_certificates(roots)
+        };
+
+        let config = match &self.client_cert {
+            Some((certs, key)) => builder
+                .with_client_auth_cert(certs.clone(), key.clone_key())
+                .map_err(tls_error)?,
+            None => builder.with_no_client_auth(),
+        };
+
+        Ok(Arc::new(config))
+    }
+}
+
+/// Transport that connects to the server via TLS, with the given configuration
+///
+/// NOTE: the port must be the TLS client port of the server (i.e. `secureClientPort`).
+#[derive(Debug, Clone)]
+pub struct ZK4LWTlsTransport {
+    config: ZK4LWTlsConfig,
+    tcp: ZK4LWTcpTransport,
+}
+
+impl ZK4LWTlsTransport {
+    pub fn new(config: ZK4LWTlsConfig) -> Self {
+        ZK4LWTlsTransport {
+            config,
+            tcp: ZK4LWTcpTransport::new(),
+        }
+    }
+
+    /// Tunnel the connections to the server through the given proxy
+    pub fn with_proxy(mut self, proxy: ZK4LWProxy) -> Self {
+        self.tcp = self.tcp.with_proxy(proxy);
+        self
+    }
+
+    pub fn config(&self) -> &ZK4LWTlsConfig {
+        &self.config
+    }
+
+    pub fn proxy(&self) -> Option<&ZK4LWProxy> {
+        self.tcp.proxy()
+    }
+}
+
+impl ZK4LWTransport for ZK4LWTlsTransport {
+    /// Connects to the server, and completes the TLS handshake within the given timeout
+    fn open(
+        &self,
+        host: &str,
+        port: u16,
+        timeout: Option<Duration>,
+    ) -> ZK4LWResult<Box<dyn ZK4LWConnection>> {
+        let mut conn = self.config.client_connection(host)?;
+
+        let mut stream = self.tcp.connect(host, port, timeout)?;
+        stream.set_read_timeout(timeout)?;
+        stream.set_write_timeout(timeout)?;
+        while conn.is_handshaking() {
+            conn.complete_io(&mut stream).map_err(handshake_error)?;
+        }
+
+        Ok(Box::new(ZK4LWStreamConnection(StreamOwned::new(
+            conn, stream,
+        ))))
+    }
+}
+
+impl ZK4LWStream for StreamOwned<ClientConnection, net::TcpStream> {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.sock.set_read_timeout(timeout)
+    }
+
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.sock.set_write_timeout(timeout)
+    }
+}
+
+/// Converts an I/O error encountered during the TLS handshake, recognising TLS errors
+fn handshake_error(error: io::Error) -> ZK4LWError {
+    match error
+        .get_ref()
+        .and_then(|e| e.downcast_ref::<rustls::Error>())
+    {
+        Some(e) => ZK4LWError::TlsError(e.to_string()),
+        None => phase_error(error, ZK4LWPhase::CONNECT),
+    }
+}
+
+fn tls_error(error: rustls::Error) -> ZK4LWError {
+    ZK4LWError::TlsError(error.to_string())
+}
+
+fn certs_from_pem(pem: &[u8]) -> ZK4LWResult<Vec<CertificateDer<'static>>> {
+    let certs = rustls_pemfile::certs(&mut &pem[..]).collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(ZK4LWError::TlsError("No certificate found in PEM".into()));
+    }
+
+    Ok(certs)
+}
+
+/// Verifier that accepts any certificate from the server (see `ZK4LWTlsConfig::insecure`)
+///
+/// Signatures are still verified, as they prove the server owns the key of the certificate.
+#[derive(Debug)]
+struct InsecureServerCertVerifier(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for InsecureServerCertVerifier {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        convert::TryInto,
+        io::{self, Read, Write},
+        net,
+        sync::Arc,
+        thread,
+    };
+
+    use rcgen::{
+        BasicConstraints, Certificate, CertificateParams, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+    };
+    use rustls::{
+        crypto::ring::default_provider, server::WebPkiClientVerifier, RootCertStore, ServerConfig,
+        ServerConnection, StreamOwned,
+    };
+
+    use crate::client::ZK4LWClient;
+    use crate::commands::ruok::{ZK4LWRuok, ZK4LWRuokResponse};
+    use crate::errors::ZK4LWError;
+    use crate::transport::tls::ZK4LWTlsConfig;
+
+    const SERVER_NAME: &str = "zookeeper.local";
+
+    /// Certificate authority, with the certificates (and keys, in PEM) it signed for server and client
+    struct TestPki {
+        ca_cert: Certificate,
+        server_cert: Certificate,
+        server_key: KeyPair,
+        client_cert: Certificate,
+        client_key: KeyPair,
+    }
+
+    impl TestPki {
+        fn new() -> Self {
+            let ca_key = KeyPair::generate().unwrap();
+            let mut ca_params = CertificateParams::new(Vec::new()).unwrap();
+            ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            let ca_cert = ca_params.self_signed(&ca_key).unwrap();
+
+            let server_key = KeyPair::generate().unwrap();
+            let server_cert = CertificateParams::new(vec![SERVER_NAME.into()])
+                .unwrap()
+                .signed_by(&server_key, &ca_cert, &ca_key)
+                .unwrap();
+
+            let client_key = KeyPair::generate().unwrap();
+            let mut client_params = CertificateParams::new(vec!["zk4lw-client".into()]).unwrap();
+            client_params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+            let client_cert = client_params
+                .signed_by(&client_key, &ca_cert, &ca_key)
+                .unwrap();
+
+            TestPki {
+                ca_cert,
+                server_cert,
+                server_key,
+                client_cert,
+                client_key,
+            }
+        }
+
+        fn server_config(&self, client_auth: bool) -> Arc<ServerConfig> {
+            let provider = Arc::new(default_provider());
+            let builder = ServerConfig::builder_with_provider(provider.clone())
+                .with_safe_default_protocol_versions()
+                .unwrap();
+            let builder = if client_auth {
+                let mut roots = RootCertStore::empty();
+                roots.add(self.ca_cert.der().clone()).unwrap();
+                let verifier = WebPkiClientVerifier::builder_with_provider(roots.into(), provider)
+                    .build()
+                    .unwrap();
+                builder.with_client_cert_verifier(verifier)
+            } else {
+                builder.with_no_client_auth()
+            };
+
+            let key = self.server_key.serialize_der().try_into().unwrap();
+            Arc::new(
+                builder
+                    .with_single_cert(vec![self.server_cert.der().clone()], key)
+                    .unwrap(),
+            )
+        }
+    }
+
+    /// Spawns a TLS server that answers a single request with the given response,
+    /// then closes the connection; the server thread returns the request it received
+    fn serve_tls_once(
+        config: Arc<ServerConfig>,
+        response: &'static str,
+    ) -> (u16, thread::JoinHandle<io::Result<[u8; 4]>>) {
+        let listener = net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept()?;
+            let conn = ServerConnection::new(config).map_err(io::Error::other)?;
+            let mut stream = StreamOwned::new(conn, stream);
+
+            let mut request = [0u8; 4];
+            stream.read_exact(&mut request)?;
+            stream.write_all(response.as_bytes())?;
+            stream.conn.send_close_notify();
+            stream.flush()?;
+            Ok(request)
+        });
+
+        (port, server)
+    }
+
+    #[test]
+    fn should_execute_commands_over_tls() {
+        let pki = TestPki::new();
+        let (port, server) = serve_tls_once(pki.server_config(true), "imok");
+
+        let tls = ZK4LWTlsConfig::new()
+            .ca_cert_pem(pki.ca_cert.pem().as_bytes())
+            .unwrap()
+            .client_cert_pem(
+                pki.client_cert.pem().as_bytes(),
+                pki.client_key.serialize_pem().as_bytes(),
+            )
+            .unwrap()
+            .server_name(SERVER_NAME);
+        let client = ZK4LWClient::builder()
+            .host("127.0.0.1")
+            .port(port)
+            .tls(tls)
+            .build();
+        assert_eq!(
+            client.execute::<ZK4LWRuok>().unwrap(),
+            ZK4LWRuokResponse::IMOK
+        );
+        assert_eq!(&server.join().unwrap().unwrap(), b"ruok");
+    }
+
+    #[test]
+    fn should_verify_certificate_of_the_server() {
+        let pki = TestPki::new();
+
+        // The certificate authority of the server is not trusted
+        let (port, server) = serve_tls_once(pki.server_config(false), "imok");
+        let client = ZK4LWClient::builder()
+            .host("127.0.0.1")
+            .port(port)
+            .tls(ZK4LWTlsConfig::new().server_name(SERVER_NAME))
+            .build();
+        match client.execute::<ZK4LWRuok>() {
+            Err(ZK4LWError::TlsError(_)) => {}
+            _ => panic!("Expected TlsError"),
+        }
+        assert!(server.join().unwrap().is_err());
+
+        // The certificate of the server is not valid for the host
+        let (port, server) = serve_tls_once(pki.server_config(false), "imok");
+        let tls = ZK4LWTlsConfig::new()
+            .ca_cert_pem(pki.ca_cert.pem().as_bytes())
+            .unwrap();
+        let client = ZK4LWClient::builder()
+            .host("127.0.0.1")
+            .port(port)
+            .tls(tls)
+            .build();
+        match client.execute::<ZK4LWRuok>() {
+            Err(ZK4LWError::TlsError(_)) => {}
+            _ => panic!("Expected TlsError"),
+        }
+        assert!(server.join().unwrap().is_err());
+
+        // Unless verification is skipped
+        let (port, server) = serve_tls_once(pki.server_config(false), "imok");
+        let client = ZK4LWClient::builder()
+            .host("127.0.0.1")
+            .port(port)
+            .tls(ZK4LWTlsConfig::new().insecure(true))
+            .build();
+        assert_eq!(
+            client.execute::<ZK4LWRuok>().unwrap(),
+            ZK4LWRuokResponse::IMOK
+        );
+        assert_eq!(&server.join().unwrap().unwrap(), b"ruok");
+    }
+
+    #[test]
+    fn should_refuse_invalid_pem() {
+        match ZK4LWTlsConfig::new().ca_cert_pem(b"not a certificate") {
+            Err(ZK4LWError::TlsError(_)) => {}
+            _ => panic!("Expected TlsError"),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Transport that connects to a Unix domain socket, that relays to the server.
+
+use std::{
+    io,
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{result::*, transport::*};
+
+/// Transport that connects to a Unix domain socket, that relays to the server
+///
+/// This is useful to route commands through a tunnel to a jump-host
+/// (e.g. `ssh -L /tmp/zk.sock:zk1:2181 jump-host`): the relay decides the server,
+/// so the host and port of the client are ignored.
+///
+/// NOTE: only available on Unix platforms.
+#[derive(Debug, Clone)]
+pub struct ZK4LWUnixRelayTransport {
+    path: PathBuf,
+}
+
+impl ZK4LWUnixRelayTransport {
+    /// # Arguments
+    /// * `path` - path of the Unix domain socket that relays to the server
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        ZK4LWUnixRelayTransport { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl ZK4LWTransport for ZK4LWUnixRelayTransport {
+    /// Connects to the relay (connecting to a Unix domain socket doesn't block, so there is no timeout)
+    fn open(
+        &self,
+        _host: &str,
+        _port: u16,
+        _timeout: Option<Duration>,
+    ) -> ZK4LWResult<Box<dyn ZK4LWConnection>> {
+        let stream = UnixStream::connect(&self.path)?;
+        Ok(Box::new(ZK4LWStreamConnection(stream)))
+    }
+}
+
+impl ZK4LWStream for UnixStream {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        UnixStream::set_read_timeout(self, timeout)
+    }
+
+    fn set_write_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        UnixStream::set_write_timeout(self, timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs,
+        io::{Read, Write},
+        os::unix::net::UnixListener,
+        process, thread,
+    };
+
+    use crate::client::ZK4LWClient;
+    use crate::commands::ruok::{ZK4LWRuok, ZK4LWRuokResponse};
+    use crate::errors::ZK4LWError;
+    use crate::transport::unix::ZK4LWUnixRelayTransport;
+
+    #[test]
+    fn should_execute_commands_via_unix_relay() {
+        let path = env::temp_dir().join(format!("zk4lw-relay-{}.sock", process::id()));
+        let _ = fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        // Relay that answers a single request, as the server behind it would
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 4];
+            stream.read_exact(&mut request).unwrap();
+            assert_eq!(&request, b"ruok");
+            stream.write_all(b"imok").unwrap();
+        });
+
+        // Host and port are ignored
+        let client = ZK4LWClient::builder()
+            .host("zk1.internal")
+            .transport(ZK4LWUnixRelayTransport::new(&path))
+            .build();
+        assert_eq!(
+            client.execute::<ZK4LWRuok>().unwrap(),
+            ZK4LWRuokResponse::IMOK
+        );
+        server.join().unwrap();
+
+        // Relay is gone
+        fs::remove_file(&path).unwrap();
+        match client.execute::<ZK4LWRuok>() {
+            Err(ZK4LWError::IoError(_)) => {}
+            _ => panic!("Expected IoError"),
+        }
+    }
+}
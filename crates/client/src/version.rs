@@ -0,0 +1,238 @@
+//! Representation of the Version of a Zookeeper Server.
+
+use std::{cmp::Ordering, fmt, str, time::SystemTime};
+
+use crate::{errors::*, parsing::*};
+
+const NUMBERS_SEPARATOR: char = '.';
+const QUALIFIER_SEPARATOR: char = '-';
+const BUILD_SEPARATOR: char = ',';
+const BUILD_DATE_PREFIX: &str = "built on";
+
+/// The version of a Zookeeper server, as reported for example by the Monitor command
+///
+/// The server reports it like `3.5.8-f439ca583e70862c3068a1f2a7d4d068eec33315, built on 05/04/2020 15:07 GMT`,
+/// where the version numbers can be followed by a qualifier (e.g. `3.5.5-SNAPSHOT-<revision>`).
+/// Parsing a version without revision and build date (e.g. `3.5.0`) is also supported,
+/// so that it's easy to compare against a reported one.
+///
+/// NOTE: Versions are compared (and considered equal) only by numbers and qualifier:
+/// a numeric qualifier is a packaging revision (e.g. `3.4.14-1`), that comes after the same version
+/// without one (e.g. `3.4.14`), while any other qualifier (e.g. `3.5.5-SNAPSHOT`) comes before it,
+/// as for Semantic Versioning pre-releases.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub qualifier: Option<String>,
+    /// Revision of the source code the server was built from
+    pub revision: Option<String>,
+    /// Build date, as reported by the server (e.g. `05/04/2020 15:07 GMT`)
+    pub build_date: Option<String>,
+    /// Build date: only available when the server reports it in the UTC (or GMT) time zone
+    ///
+    /// NOTE: This is `None` also when `build_date` is set, but it can't be parsed.
+    pub build_time: Option<SystemTime>,
+}
+
+impl ZK4LWVersion {
+    pub fn new(major: u32, minor: u32, patch: u32) -> Self {
+        ZK4LWVersion {
+            major,
+            minor,
+            patch,
+            ..Default::default()
+        }
+    }
+}
+
+impl str::FromStr for ZK4LWVersion {
+    type Err = ZK4LWError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_err = || {
+            ZK4LWError::ParseStringError(format!("Unable to parse version from string: '{}'", s))
+        };
+
+        let mut version = ZK4LWVersion::default();
+
+        // Split revision and build date (i.e. `<version>-<revision>, built on <date>`), if reported
+        let numbers_and_qualifier = match s.split_once(BUILD_SEPARATOR) {
+            Some((numbers_and_revision, build_date)) => {
+                let (numbers_and_qualifier, revision) = numbers_and_revision
+                    .trim()
+                    .rsplit_once(QUALIFIER_SEPARATOR)
+                    .ok_or_else(parse_err)?;
+                let build_date = build_date
+                    .trim()
+                    .trim_start_matches(BUILD_DATE_PREFIX)
+                    .trim();
+                version.revision = Some(revision.to_string()).filter(|r| !r.is_empty());
+                version.build_date = Some(build_date.to_string()).filter(|d| !d.is_empty());
+                version.build_time = build_date_to_system_time(build_date).ok();
+                numbers_and_qualifier
+            }
+            None => s.trim(),
+        };
+
+        // Split the qualifier (e.g. `3.5.5-SNAPSHOT`), if any
+        // NOTE: ZK >= 3.6.x reports an empty qualifier (e.g. `3.6.1--<revision>`)
+        let numbers = match numbers_and_qualifier.split_once(QUALIFIER_SEPARATOR) {
+            Some((numbers, qualifier)) => {
+                version.qualifier = Some(qualifier.to_string()).filter(|q| !q.is_empty());
+                numbers
+            }
+            None => numbers_and_qualifier,
+        };
+
+        let numbers = numbers
+            .split(NUMBERS_SEPARATOR)
+            .map(str::parse)
+            .collect::<Result<Vec<u32>, _>>()?;
+        match numbers.as_slice() {
+            [major, minor, patch] => {
+                version.major = *major;
+                version.minor = *minor;
+                version.patch = *patch;
+            }
+            _ => return Err(parse_err()),
+        }
+
+        Ok(version)
+    }
+}
+
+impl fmt::Display for ZK4LWVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(qualifier) = &self.qualifier {
+            write!(f, "{}{}", QUALIFIER_SEPARATOR, qualifier)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the key to sort versions with the same numbers by qualifier: pre-releases first
+/// (e.g. `SNAPSHOT`, alphabetically), then no qualifier, then packaging revisions (e.g. `1`, numerically)
+fn qualifier_sort_key(qualifier: &Option<String>) -> (u8, u64, &str) {
+    match qualifier.as_deref() {
+        None => (1, 0, ""),
+        Some(q) => match q.parse() {
+            Ok(revision) => (2, revision, ""),
+            Err(_) => (0, 0, q),
+        },
+    }
+}
+
+impl Ord for ZK4LWVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| {
+                qualifier_sort_key(&self.qualifier).cmp(&qualifier_sort_key(&other.qualifier))
+            })
+    }
+}
+
+impl PartialOrd for ZK4LWVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for ZK4LWVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ZK4LWVersion {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::version::ZK4LWVersion;
+
+    #[test]
+    fn should_parse_version() {
+        let version: ZK4LWVersion =
+            "3.5.8-f439ca583e70862c3068a1f2a7d4d068eec33315, built on 05/04/2020 15:07 GMT"
+                .parse()
+                .unwrap();
+        assert_eq!(version, ZK4LWVersion::new(3, 5, 8));
+        assert_eq!(version.qualifier, None);
+        assert_eq!(
+            version.revision.as_deref(),
+            Some("f439ca583e70862c3068a1f2a7d4d068eec33315")
+        );
+        assert_eq!(version.build_date.as_deref(), Some("05/04/2020 15:07 GMT"));
+        assert_eq!(
+            version.build_time,
+            Some(UNIX_EPOCH + Duration::from_secs(1588604820))
+        );
+
+        // Empty qualifier
+        let version: ZK4LWVersion =
+            "3.6.1--104dcb3e3fb464b30c5186d229e00af9f332524b, built on 04/21/2020 15:01 GMT"
+                .parse()
+                .unwrap();
+        assert_eq!(version.to_string(), "3.6.1");
+        assert_eq!(
+            version.revision.as_deref(),
+            Some("104dcb3e3fb464b30c5186d229e00af9f332524b")
+        );
+
+        // Qualifiers
+        let version: ZK4LWVersion = "3.5.5-SNAPSHOT-c49e6ea4a1de4ddce7a4b1b6c9bc18a0f6e0fd26, built on 05/03/2019 12:07 GMT"
+            .parse()
+            .unwrap();
+        assert_eq!(version.qualifier.as_deref(), Some("SNAPSHOT"));
+        assert_eq!(version.to_string(), "3.5.5-SNAPSHOT");
+        let version: ZK4LWVersion =
+            "3.4.14-1-4c25d480e66aadd371de8bd2fd8da255ac140bcf, built on 03/06/2019 16:18 GMT"
+                .parse()
+                .unwrap();
+        assert_eq!(version.qualifier.as_deref(), Some("1"));
+        assert_eq!(
+            version.revision.as_deref(),
+            Some("4c25d480e66aadd371de8bd2fd8da255ac140bcf")
+        );
+
+        // Without revision and build date
+        let version: ZK4LWVersion = "3.5.0".parse().unwrap();
+        assert_eq!(version, ZK4LWVersion::new(3, 5, 0));
+        assert_eq!(version.revision, None);
+        assert_eq!(version.build_time, None);
+
+        assert!("3.5".parse::<ZK4LWVersion>().is_err());
+        assert!("3.5.x".parse::<ZK4LWVersion>().is_err());
+        assert!("".parse::<ZK4LWVersion>().is_err());
+    }
+
+    #[test]
+    fn should_compare_versions() {
+        let v: ZK4LWVersion =
+            "3.5.8-f439ca583e70862c3068a1f2a7d4d068eec33315, built on 05/04/2020 15:07 GMT"
+                .parse()
+                .unwrap();
+        assert!(v >= "3.5.0".parse().unwrap());
+        assert!(v < "3.6.0".parse().unwrap());
+        assert!(v > "3.4.14".parse().unwrap());
+        assert!(v == "3.5.8".parse().unwrap());
+
+        let snapshot: ZK4LWVersion = "3.5.8-SNAPSHOT".parse().unwrap();
+        assert!(snapshot < v);
+        assert!(snapshot > ZK4LWVersion::new(3, 5, 7));
+
+        // Packaging revisions
+        let revision: ZK4LWVersion = "3.4.14-1".parse().unwrap();
+        assert!(revision >= ZK4LWVersion::new(3, 4, 14));
+        assert!(revision < ZK4LWVersion::new(3, 4, 15));
+        assert!(revision > "3.4.14-SNAPSHOT".parse().unwrap());
+        assert!("3.4.14-10".parse::<ZK4LWVersion>().unwrap() > "3.4.14-9".parse().unwrap());
+        assert!("3.5.5-beta".parse::<ZK4LWVersion>().unwrap() > "3.5.5-alpha".parse().unwrap());
+    }
+}
@@ -0,0 +1,100 @@
+//! Representation of a Zookeeper Transaction Id (i.e. "Zxid").
+
+use std::{fmt, str};
+
+use crate::{errors::*, parsing::*};
+
+const EPOCH_SHIFT: u32 = 32;
+
+/// A Zookeeper Transaction Id (i.e. "Zxid"), as reported for example by the Server command
+///
+/// It's a 64 bits number: the high 32 bits are the epoch (i.e. it changes every time a new
+/// "Leader" is elected), while the low 32 bits are a counter of the transactions in the epoch.
+/// Zxids are ordered like the transactions they identify.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZK4LWZxid(pub u64);
+
+impl ZK4LWZxid {
+    pub fn new(epoch: u32, counter: u32) -> Self {
+        ZK4LWZxid(((epoch as u64) << EPOCH_SHIFT) | counter as u64)
+    }
+
+    /// Epoch of the "Leader" that proposed the transaction
+    pub fn epoch(&self) -> u32 {
+        (self.0 >> EPOCH_SHIFT) as u32
+    }
+
+    /// Counter of the transaction, within the epoch
+    pub fn counter(&self) -> u32 {
+        self.0 as u32
+    }
+
+    /// Returns how many transactions this Zxid is behind the given one (e.g. the "Leader"'s)
+    ///
+    /// Returns `None` if the epochs differ, as transactions can't be counted across epochs.
+    pub fn transactions_behind(&self, other: &ZK4LWZxid) -> Option<u32> {
+        if self.epoch() == other.epoch() {
+            Some(other.counter().saturating_sub(self.counter()))
+        } else {
+            None
+        }
+    }
+}
+
+impl From<u64> for ZK4LWZxid {
+    fn from(zxid: u64) -> Self {
+        ZK4LWZxid(zxid)
+    }
+}
+
+impl str::FromStr for ZK4LWZxid {
+    type Err = ZK4LWError;
+
+    /// Parses the hexadecimal form of the Zxid, with or without `0x` prefix (e.g. `0x100000003`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(ZK4LWZxid(hex_to_u64(s)?))
+    }
+}
+
+impl fmt::Display for ZK4LWZxid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::zxid::ZK4LWZxid;
+
+    #[test]
+    fn should_parse_zxid() {
+        let zxid: ZK4LWZxid = "0x2000001a4".parse().unwrap();
+        assert_eq!(zxid, ZK4LWZxid(0x2_0000_01a4));
+        assert_eq!(zxid, ZK4LWZxid::new(2, 0x1a4));
+        assert_eq!(zxid.epoch(), 2);
+        assert_eq!(zxid.counter(), 420);
+        assert_eq!(zxid.to_string(), "0x2000001a4");
+
+        // Without prefix (e.g. as reported by `hash`)
+        assert_eq!(
+            "300000001".parse::<ZK4LWZxid>().unwrap(),
+            ZK4LWZxid::new(3, 1)
+        );
+
+        assert!("0xnot-a-zxid".parse::<ZK4LWZxid>().is_err());
+    }
+
+    #[test]
+    fn should_compare_zxids() {
+        let leader = ZK4LWZxid::new(2, 10);
+        let follower = ZK4LWZxid::new(2, 7);
+        let stale = ZK4LWZxid::new(1, 1000);
+
+        assert!(follower < leader);
+        assert!(stale < follower);
+        assert_eq!(follower.transactions_behind(&leader), Some(3));
+        assert_eq!(leader.transactions_behind(&follower), Some(0));
+        assert_eq!(stale.transactions_behind(&leader), None);
+    }
+}